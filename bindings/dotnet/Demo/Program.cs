@@ -18,7 +18,7 @@ internal class Program
         PreonColor foregroundColor = PreonColor.Hex("#d3d3d3")!.Value;
         PreonEngine engine = new();
 
-        engine.Tree = PreonComponent.StartBuilder(engine)
+        engine.SetTree(PreonComponent.StartBuilder(engine)
             .FontSize(32.0f)
             .BackgroundColor(backgroundColor)
             .ForegroundColor(foregroundColor)
@@ -57,7 +57,7 @@ internal class Program
                     .End()
                 .End()
             .End()
-        .Build();
+        );
 
         engine.OnResized += newSize =>
         {
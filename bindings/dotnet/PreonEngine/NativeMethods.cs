@@ -10,7 +10,16 @@ namespace Preon;
 
 internal static class NativeMethods
 {
-    public unsafe delegate bool RunCallback(void* tree, PreonEventBinding two, PreonUserEventEmitterBinding three);
+    public unsafe delegate bool RunCallback(PreonComponentBinding tree, PreonEventBinding two, PreonUserEventEmitterBinding three);
+
+    // A component inside of an engine, same layout as PreonComponentBinding in src/lib.rs
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe struct PreonComponentBinding
+    {
+        internal void* Engine;
+        internal uint Index;
+        internal uint Generation;
+    }
 
     [StructLayout(LayoutKind.Sequential)]
     public struct PreonEventBinding
@@ -71,6 +80,9 @@ internal static class NativeMethods
     [DllImport("PreonEngine", EntryPoint = "PreonEngine__new", CallingConvention = CallingConvention.Cdecl)]
     public static unsafe extern void* PreonEngine__new();
     
+    [DllImport("PreonEngine", EntryPoint = "PreonEngine__root", CallingConvention = CallingConvention.Cdecl)]
+    public static unsafe extern PreonComponentBinding PreonEngine__root(void* _0);
+    
     [DllImport("PreonEngine", EntryPoint = "PreonEngine__set_tree", CallingConvention = CallingConvention.Cdecl)]
     public static unsafe extern void PreonEngine__set_tree(void* _0, void* _1);
     
@@ -86,8 +98,8 @@ internal static class NativeMethods
     [DllImport("PreonEngine", EntryPoint = "PreonComponentBuilder__end", CallingConvention = CallingConvention.Cdecl)]
     public static unsafe extern void PreonComponentBuilder__end(void* _0);
     
-    [DllImport("PreonEngine", EntryPoint = "PreonComponentBuilder__build", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void* PreonComponentBuilder__build(void* _0);
+    [DllImport("PreonEngine", EntryPoint = "PreonComponentBuilder__build_into", CallingConvention = CallingConvention.Cdecl)]
+    public static unsafe extern PreonComponentBinding PreonComponentBuilder__build_into(void* _0, PreonComponentBinding _1);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponentBuilder__start_hbox", CallingConvention = CallingConvention.Cdecl)]
     public static unsafe extern void PreonComponentBuilder__start_hbox(void* _0);
@@ -192,28 +204,25 @@ internal static class NativeMethods
     public static unsafe extern void PreonEventEmitter__push(void* _0, PreonEventBinding _1);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponent__set_text", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void PreonComponent__set_text(void* _0, string _1);
+    public static unsafe extern void PreonComponent__set_text(PreonComponentBinding _0, string _1);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponent__get_text", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern string PreonComponent__get_text(void* _0);
-    
-    [DllImport("PreonEngine", EntryPoint = "PreonComponent__new", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void* PreonComponent__new();
+    public static unsafe extern string PreonComponent__get_text(PreonComponentBinding _0);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponent__add_child", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void PreonComponent__add_child(void* _0, void* _1);
+    public static unsafe extern void PreonComponent__add_child(PreonComponentBinding _0, PreonComponentBinding _1);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponent__insert_child", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void PreonComponent__insert_child(void* _0, ushort _1, void* _2);
+    public static unsafe extern void PreonComponent__insert_child(PreonComponentBinding _0, ushort _1, PreonComponentBinding _2);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponent__remove_child", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void PreonComponent__remove_child(void* _0, ushort _1);
+    public static unsafe extern void PreonComponent__remove_child(PreonComponentBinding _0, ushort _1);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponent__clear_children", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void PreonComponent__clear_children(void* _0);
+    public static unsafe extern void PreonComponent__clear_children(PreonComponentBinding _0);
     
     [DllImport("PreonEngine", EntryPoint = "PreonComponent__get_child_ref_mut_by_id", CallingConvention = CallingConvention.Cdecl)]
-    public static unsafe extern void* PreonComponent__get_child_ref_mut_by_id(void* _0, string _1);
+    public static unsafe extern PreonComponentBinding PreonComponent__get_child_ref_mut_by_id(PreonComponentBinding _0, string _1);
     
     [DllImport("PreonEngine", EntryPoint = "preon__init", CallingConvention = CallingConvention.Cdecl)]
     public static unsafe extern void preon__init();
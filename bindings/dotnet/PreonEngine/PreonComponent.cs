@@ -8,11 +8,24 @@ public class PreonComponent
         set { unsafe { NativeMethods.PreonComponent__set_text(_inner, value); } }
     }
 
-    internal unsafe void* _inner;
+    private readonly PreonEngine _engine;
+    private readonly NativeMethods.PreonComponentBinding _binding;
 
-    internal unsafe PreonComponent(void* ptr)
+    // The engine moves when it starts running, so its current address is filled in on every call
+    internal unsafe NativeMethods.PreonComponentBinding _inner
     {
-        _inner = ptr;
+        get
+        {
+            NativeMethods.PreonComponentBinding binding = _binding;
+            binding.Engine = _engine._inner;
+            return binding;
+        }
+    }
+
+    internal PreonComponent(PreonEngine engine, NativeMethods.PreonComponentBinding binding)
+    {
+        _engine = engine;
+        _binding = binding;
     }
 
     public void AddChild(PreonComponent child)
@@ -52,7 +65,7 @@ public class PreonComponent
     {
         unsafe
         {
-            return new PreonComponent(NativeMethods.PreonComponent__get_child_ref_mut_by_id(_inner, id));
+            return new PreonComponent(_engine, NativeMethods.PreonComponent__get_child_ref_mut_by_id(_inner, id));
         }
     }
 
@@ -5,7 +5,7 @@ namespace Preon;
 
 public class PreonComponentBuilder
 {
-    private unsafe void* _inner;
+    internal unsafe void* _inner;
     public PreonEngine EngineRef;
 
     public PreonComponentBuilder(PreonEngine engineRef)
@@ -381,11 +381,12 @@ public class PreonComponentBuilder
         return this;
     }
 
-    public PreonComponent Build()
+    // Builds everything into the engine as the last child of `parent`, and returns the root of it
+    public PreonComponent BuildInto(PreonComponent parent)
     {
         unsafe
         {
-            return new PreonComponent(NativeMethods.PreonComponentBuilder__build(_inner));
+            return new PreonComponent(EngineRef, NativeMethods.PreonComponentBuilder__build_into(_inner, parent._inner));
         }
     }
 }
\ No newline at end of file
@@ -12,8 +12,7 @@ public class PreonEngine
     public delegate void ResizedCallback(PreonVector<uint> newSize);
     public delegate void ButtonCallback(PreonComponent pressed, PreonButtonState state);
 
-    private unsafe void* _inner;
-    private PreonComponent? _tree;
+    internal unsafe void* _inner;
     private bool _forceUpdate;
 
     public event GenericCallback? OnWindowOpened;
@@ -26,10 +25,10 @@ public class PreonEngine
     public event ReceivedCharacterCallback? OnReceivedCharacter;
     public Dictionary<string, ButtonCallback> _buttonCallbacks;
 
+    // The root component, build into it with PreonEngine.SetTree or PreonComponentBuilder.BuildInto
     public PreonComponent Tree
     {
-        get { return _tree!; }
-        set { _tree = value; unsafe { NativeMethods.PreonEngine__set_tree(_inner, value._inner); } }
+        get { unsafe { return new PreonComponent(this, NativeMethods.PreonEngine__root(_inner)); } }
     }
 
 
@@ -45,11 +44,12 @@ public class PreonEngine
         OnWindowOpened += () => ForceUpdate();
     }
 
-    public void SetTree(PreonComponent tree)
+    // Replaces the children of the root component with everything built with `builder`
+    public void SetTree(PreonComponentBuilder builder)
     {
         unsafe
         {
-            NativeMethods.PreonEngine__set_tree(_inner, tree._inner);
+            NativeMethods.PreonEngine__set_tree(_inner, builder._inner);
         }
     }
 
@@ -73,17 +73,15 @@ public class PreonEngine
     {
         unsafe
         {
-            _tree = new PreonComponent(null);
-        
             NativeMethods.preon__run(_inner, (tree, @event, userEvents) => {
-                _tree._inner = tree;
+                _inner = tree.Engine;
                 _forceUpdate = false;
 
                 switch (NativeMethods.Unbind(@event))
                 {
                     case PreonEvent.ComponentPressed pressedEvent:
                         if (_buttonCallbacks.TryGetValue(pressedEvent.Id, out ButtonCallback? buttonCallback))
-                            buttonCallback.Invoke(_tree.GetChildById(pressedEvent.Id), pressedEvent.State);
+                            buttonCallback.Invoke(Tree.GetChildById(pressedEvent.Id), pressedEvent.State);
                         break;
                     
                     case PreonEvent.WindowResized resizedEvent: OnResized?.Invoke(resizedEvent.NewSize); break;
@@ -2,16 +2,18 @@ import os
 import os.path
 
 PTR = "void*"
+COMPONENT = "PreonComponentBinding"
 BINDINGS = [
-    [PTR,    "PreonEngine__new",      []],
-    ["void", "PreonEngine__set_tree", [PTR, PTR]],
+    [PTR,       "PreonEngine__new",      []],
+    [COMPONENT, "PreonEngine__root",     [PTR]],
+    ["void",    "PreonEngine__set_tree", [PTR, PTR]],
 
 
     [PTR,    "PreonComponentBuilder__new",            []],
     ["void", "PreonComponentBuilder__id_string",      [PTR, "string"]],
     ["void", "PreonComponentBuilder__receive_events", [PTR, "bool"]],
     ["void", "PreonComponentBuilder__end",            [PTR]],
-    [PTR,    "PreonComponentBuilder__build",          [PTR]],
+    [COMPONENT, "PreonComponentBuilder__build_into",  [PTR, COMPONENT]],
 
     ["void",    "PreonComponentBuilder__start_hbox", [PTR]],
     ["void",    "PreonComponentBuilder__empty_hbox", [PTR]],
@@ -56,16 +58,15 @@ BINDINGS = [
 
     ["void", "PreonEventEmitter__push", [PTR, "PreonEventBinding"]],
 
-    ["void",       "PreonComponent__set_text",  [PTR, "string"]],
-    ["string",     "PreonComponent__get_text",  [PTR]],
+    ["void",       "PreonComponent__set_text",  [COMPONENT, "string"]],
+    ["string",     "PreonComponent__get_text",  [COMPONENT]],
 
-    [PTR,      "PreonComponent__new",            []],
-    ["void",   "PreonComponent__add_child",      [PTR, PTR]],
-    ["void",   "PreonComponent__insert_child",   [PTR, "ushort", PTR]],
-    ["void",   "PreonComponent__remove_child",   [PTR, "ushort"]],
-    ["void",   "PreonComponent__clear_children", [PTR]],
+    ["void",   "PreonComponent__add_child",      [COMPONENT, COMPONENT]],
+    ["void",   "PreonComponent__insert_child",   [COMPONENT, "ushort", COMPONENT]],
+    ["void",   "PreonComponent__remove_child",   [COMPONENT, "ushort"]],
+    ["void",   "PreonComponent__clear_children", [COMPONENT]],
 
-    [PTR,      "PreonComponent__get_child_ref_mut_by_id", [PTR, "string"]],
+    [COMPONENT, "PreonComponent__get_child_ref_mut_by_id", [COMPONENT, "string"]],
 
     ["void", "preon__init",           []],
     ["void", "preon__run",            [PTR, "[MarshalAs(UnmanagedType.FunctionPtr)]RunCallback"]],
@@ -94,7 +95,16 @@ namespace Preon;
 
 internal static class NativeMethods
 {{
-    public unsafe delegate bool RunCallback({PTR} tree, PreonEventBinding two, PreonUserEventEmitterBinding three);
+    public unsafe delegate bool RunCallback({COMPONENT} tree, PreonEventBinding two, PreonUserEventEmitterBinding three);
+
+    // A component inside of an engine, same layout as PreonComponentBinding in src/lib.rs
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe struct PreonComponentBinding
+    {{
+        internal void* Engine;
+        internal uint Index;
+        internal uint Generation;
+    }}
 
     [StructLayout(LayoutKind.Sequential)]
     public struct PreonEventBinding
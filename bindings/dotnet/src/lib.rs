@@ -28,6 +28,9 @@ pub struct DataHolder<T: Copy>(T);
 unsafe impl<T: Copy> bytemuck::Zeroable for DataHolder<T> {}
 unsafe impl<T: Copy + 'static> bytemuck::Pod for DataHolder<T> {}
 
+/// Events that aren't available in .NET yet, these are never passed to the callback.
+const UNSUPPORTED_EVENT_KIND: u8 = u8::MAX;
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct PreonEventBinding {
@@ -74,7 +77,7 @@ impl From<preon_engine::events::PreonEvent> for PreonEventBinding {
                 button_state: state,
                 ..PreonEventBinding::from_kind(5)
             },
-            preon_engine::prelude::PreonEvent::MouseInput(button, state) => PreonEventBinding {
+            preon_engine::prelude::PreonEvent::MouseInput(button, state, _) => PreonEventBinding {
                 MouseInput_button: match button {
                     preon_engine::events::PreonMouseButton::Left => 0,
                     preon_engine::events::PreonMouseButton::Middle => 1,
@@ -84,15 +87,16 @@ impl From<preon_engine::events::PreonEvent> for PreonEventBinding {
                 button_state: state,
                 ..PreonEventBinding::from_kind(6)
             },
-            preon_engine::prelude::PreonEvent::KeyboardInput(key, state) => PreonEventBinding {
-                KeyboardInput_key: key,
-                button_state: state,
+            preon_engine::prelude::PreonEvent::KeyboardInput(event) => PreonEventBinding {
+                KeyboardInput_key: event.key,
+                button_state: event.state,
                 ..PreonEventBinding::from_kind(7)
             },
             preon_engine::prelude::PreonEvent::ReceivedCharacter(ch) => PreonEventBinding {
                 ReceivedCharacter_ch: ch,
                 ..PreonEventBinding::from_kind(8)
-            }
+            },
+            _ => PreonEventBinding::from_kind(UNSUPPORTED_EVENT_KIND),
         }
     }
 }
@@ -158,13 +162,45 @@ pub unsafe extern "C" fn PreonEngine__new() -> PreonEngineBinding {
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn PreonEngine__set_tree(engine: PreonEngineBinding, tree: PreonComponentBinding) {
-    engine.inner.as_mut().unwrap().set_tree(*Box::from_raw(tree.inner));
+pub unsafe extern "C" fn PreonEngine__root(engine: PreonEngineBinding) -> PreonComponentBinding {
+    PreonComponentBinding {
+        engine: engine.inner,
+        handle: engine.inner.as_ref().unwrap().root,
+    }
 }
 
-#[repr(transparent)]
+/// Replaces the children of the root component with everything built with `component_builder`.
+#[no_mangle]
+pub unsafe extern "C" fn PreonEngine__set_tree(engine: PreonEngineBinding, component_builder: PreonComponentBuilderBinding) {
+    let engine = engine.inner.as_mut().unwrap();
+    let root = engine.root;
+
+    for child in engine.get_component(root).unwrap().children.clone() {
+        engine.remove_component(child);
+    }
+
+    component_builder.inner.as_mut().unwrap().build_into(engine, root);
+}
+
+/// A component inside of an engine. Passed by value, the handle stays valid until the component
+/// is removed.
+#[derive(Clone, Copy)]
+#[repr(C)]
 pub struct PreonComponentBinding {
-    pub inner: *mut preon_engine::components::PreonComponent,
+    pub engine: *mut preon_engine::PreonEngine,
+    pub handle: preon_engine::PreonComponentHandle,
+}
+
+impl PreonComponentBinding {
+    unsafe fn engine<'a>(self) -> &'a mut preon_engine::PreonEngine {
+        self.engine.as_mut().unwrap()
+    }
+
+    unsafe fn component<'a>(self) -> &'a preon_engine::components::PreonComponent {
+        self.engine()
+            .get_component(self.handle)
+            .expect("Invalid PreonComponentHandle")
+    }
 }
 
 #[repr(transparent)]
@@ -179,10 +215,15 @@ pub unsafe extern "C" fn PreonComponentBuilder__new() -> PreonComponentBuilderBi
     }
 }
 
+/// Builds everything in `component_builder` into the engine of `parent`, as its last child.
+/// Returns the root of the built tree.
 #[no_mangle]
-pub unsafe extern "C" fn PreonComponentBuilder__build(component_builder: PreonComponentBuilderBinding) -> PreonComponentBinding {
+pub unsafe extern "C" fn PreonComponentBuilder__build_into(component_builder: PreonComponentBuilderBinding, parent: PreonComponentBinding) -> PreonComponentBinding {
+    let (handle, _) = component_builder.inner.as_mut().unwrap().build_into(parent.engine(), parent.handle);
+
     PreonComponentBinding {
-        inner: Box::into_raw(Box::new(component_builder.inner.as_mut().unwrap().build())),
+        engine: parent.engine,
+        handle,
     }
 }
 
@@ -274,12 +315,20 @@ pub unsafe extern "C" fn preon__run(engine: PreonEngineBinding, callback: extern
     let juan = engine.load_image(&include_bytes!("../../../res/juan.png")[..]);
     let font_normal = engine.load_font(&include_bytes!("../../../res/Montserrat-Regular.otf")[..]);
 
-    preon_module_wgpu::preon::run(engine, move |tree, event, user_events| {
+    preon_module_wgpu::preon::run(engine, move |engine, event, user_events| {
+        let root = engine.root;
+        let event: PreonEventBinding = event.into();
+
+        if event.kind == UNSUPPORTED_EVENT_KIND {
+            return;
+        }
+
         if callback(
             PreonComponentBinding {
-                inner: tree as *mut preon_engine::components::PreonComponent,
+                engine: engine as *mut preon_engine::PreonEngine,
+                handle: root,
             },
-            event.into(),
+            event,
             PreonUserEventEmitterBinding {
                 inner: user_events as *mut preon_engine::events::PreonEventEmitter<preon_engine::events::PreonUserEvent>,
             },
@@ -291,126 +340,132 @@ pub unsafe extern "C" fn preon__run(engine: PreonEngineBinding, callback: extern
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__get_text(component: PreonComponentBinding) -> *mut i8 {
-    let text = component.inner.as_ref().unwrap().text.as_str();
+    let text = component.component().text.as_str();
     to_cstring!(text)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__set_text(component: PreonComponentBinding, text: *const c_char) {
-    component.inner.as_mut().unwrap().text = to_string!(text);
+    component.engine().set_text(component.handle, to_string!(text));
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_background_color(component: PreonComponentBinding, color: preon_engine::types::PreonColor) {
-    component.inner.as_mut().unwrap().style.background = preon_engine::style::PreonBackground::Color(color);
+    component.engine().edit_style(component.handle, |style| style.background = preon_engine::style::PreonBackground::Color(color));
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_clear_background_color(component: PreonComponentBinding) {
-    component.inner.as_mut().unwrap().style.background = preon_engine::style::PreonBackground::None;
+    component.engine().edit_style(component.handle, |style| style.background = preon_engine::style::PreonBackground::None);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_foreground_color(component: PreonComponentBinding, color: preon_engine::types::PreonColor) {
-    component.inner.as_mut().unwrap().style.foreground_color = color;
+    component.engine().edit_style(component.handle, |style| style.foreground_color = color);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_align_items(component: PreonComponentBinding, alignment: preon_engine::types::PreonAlignment) {
-    component.inner.as_mut().unwrap().style.align_items = alignment;
+    component.engine().edit_style(component.handle, |style| style.align_items = alignment);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_cross_align_items(component: PreonComponentBinding, alignment: preon_engine::types::PreonAlignment) {
-    component.inner.as_mut().unwrap().style.cross_align_items = alignment;
+    component.engine().edit_style(component.handle, |style| style.cross_align_items = alignment);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_layout(component: PreonComponentBinding, layout: preon_engine::layout::PreonLayout) {
-    component.inner.as_mut().unwrap().style.layout = layout;
+    component.engine().edit_style(component.handle, |style| style.layout = layout);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_margin(component: PreonComponentBinding, margin: preon_engine::types::PreonBorder) {
-    component.inner.as_mut().unwrap().style.margin = margin;
+    component.engine().edit_style(component.handle, |style| style.margin = margin);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_padding(component: PreonComponentBinding, padding: preon_engine::types::PreonBorder) {
-    component.inner.as_mut().unwrap().style.padding = padding;
+    component.engine().edit_style(component.handle, |style| style.padding = padding);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_border(component: PreonComponentBinding, border: preon_engine::types::PreonBorder) {
-    component.inner.as_mut().unwrap().style.border = border;
+    component.engine().edit_style(component.handle, |style| style.border = border);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_corner_radius(component: PreonComponentBinding, corners: preon_engine::types::PreonCorners) {
-    component.inner.as_mut().unwrap().style.corner_radius = corners;
+    component.engine().edit_style(component.handle, |style| style.corner_radius = corners);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_size_flags(component: PreonComponentBinding, size_flags: u8) {
-    component.inner.as_mut().unwrap().style.size_flags = size_flags;
+    component.engine().edit_style(component.handle, |style| style.size_flags = size_flags);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__style_set_min_size(component: PreonComponentBinding, min_size: preon_engine::types::PreonVector<i32>) {
-    component.inner.as_mut().unwrap().style.min_size = min_size;
+    component.engine().edit_style(component.handle, |style| style.min_size = min_size);
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__text_style_set_font(component: PreonComponentBinding, font: PreonFontBinding) {
-    component.inner.as_mut().unwrap().style.text_style.font = Some(*Box::from_raw(font.inner));
+    let font = *Box::from_raw(font.inner);
+    component.engine().edit_style(component.handle, |style| style.text_style.font = Some(font));
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__text_style_set_font_size(component: PreonComponentBinding, font_size: f32) {
-    component.inner.as_mut().unwrap().style.text_style.size = font_size;
+    component.engine().edit_style(component.handle, |style| style.text_style.size = font_size);
 }
 
+/// Looks up the component with id `id` among `component` and its descendants.
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__get_child_ref_mut_by_id(component: PreonComponentBinding, id: *const c_char) -> PreonComponentBinding {
     let id = to_string!(id);
+    let engine = component.engine();
 
-    let inner = component
-        .inner
-        .as_mut()
-        .unwrap()
-        .get_child_raw_by_id(id.clone())
-        .expect(&format!("PreonComponent.get_child_ref_mut_by_id failed, could not find child with id {}", id));
-
-    PreonComponentBinding {
-        inner,
-    }
-}
-
-#[no_mangle]
-pub unsafe extern "C" fn PreonComponent__new() -> PreonComponentBinding {
-    let component = preon_engine::components::PreonComponent::new();
+    let handle = engine
+        .find_by_id(&id)
+        .filter(|&handle| engine.is_ancestor_or_self(component.handle, handle))
+        .unwrap_or_else(|| panic!("PreonComponent.get_child_ref_mut_by_id failed, could not find child with id {}", id));
 
     PreonComponentBinding {
-        inner: Box::into_raw(Box::new(component)),
+        engine: component.engine,
+        handle,
     }
 }
 
+/// Moves `child` (and its descendants) to the end of the children of `component`.
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__add_child(component: PreonComponentBinding, child: PreonComponentBinding) {
-    component.inner.as_mut().unwrap().add_child(*Box::from_raw(child.inner));
+    assert!(component.engine == child.engine, "Can't move components between engines");
+
+    component.engine().reparent(child.handle, component.handle, usize::MAX);
 }
 
+/// Moves `child` (and its descendants) into the children of `component`, at index `idx`.
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__insert_child(component: PreonComponentBinding, idx: u16, child: PreonComponentBinding) {
-    component.inner.as_mut().unwrap().insert_child(idx, *Box::from_raw(child.inner));
+    assert!(component.engine == child.engine, "Can't move components between engines");
+
+    component.engine().reparent(child.handle, component.handle, idx as usize);
 }
 
+/// Removes child number `idx` of `component` and all of its descendants from the engine.
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__remove_child(component: PreonComponentBinding, idx: u16) {
-    component.inner.as_mut().unwrap().remove_child(idx);
+    let child = component.component().children.get(idx as usize).copied();
+
+    if let Some(child) = child {
+        component.engine().remove_component(child);
+    }
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn PreonComponent__clear_children(component: PreonComponentBinding) {
-    component.inner.as_mut().unwrap().clear_children();
-}
\ No newline at end of file
+    for child in component.component().children.clone() {
+        component.engine().remove_component(child);
+    }
+}
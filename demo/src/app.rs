@@ -2,6 +2,7 @@ use preon_engine::prelude::*;
 use preon_engine::components::PreonComponent;
 use preon_module_wgpu::preon;
 use rand::Rng;
+use std::time::Duration;
 
 struct Heading<'a>(&'a PreonFont);
 impl<'a> PreonClass for Heading<'a> {
@@ -24,23 +25,27 @@ pub fn app() {
     let font_normal = engine.load_font(&include_bytes!("../../res/Montserrat-Regular.otf")[..]);
     let font_bold = engine.load_font(&include_bytes!("../../res/Montserrat-Bold.otf")[..]);
     
+    let root = engine.root;
+
     #[rustfmt::skip]
-    engine.set_tree(
-        PreonComponentBuilder::new()
-            .start_panel_hex("#da0037")
-                .min_size(PreonVector::new(0, 60))
-                .expand_horizontally()
-                .start_static_texture(&wood_man)
-                    .margin(PreonBorder::new(0, 0, -50, 0))
-                    .min_size(PreonVector::new(200, 200))
-                .end()
+    PreonComponentBuilder::new()
+        .expand()
+        .start_panel_hex("#da0037")
+            .min_size(PreonVector::new(0, 60))
+            .expand_horizontally()
+            .start_static_texture(&wood_man)
+                .margin(PreonBorder::new(0, 0, -50, 0))
+                .min_size(PreonVector::new(200, 200))
             .end()
-            .start_hbox()
-                .expand()
-                .start_panel_hex("#ffffff")
-                    .min_size(PreonVector::new(300, 0))
-                    .expand_vertically()
-                    .padding(PreonBorder::from_single(16))
+        .end()
+        .start_hbox()
+            .expand()
+            .start_panel_hex("#ffffff")
+                .min_size(PreonVector::new(300, 0))
+                .expand_vertically()
+                .padding(PreonBorder::from_single(16))
+                .start_scroll_view()
+                    .inertial_scrolling(true)
                     .start_vbox()
                         .fit_children_vertically()
                         .expand_horizontally()
@@ -48,6 +53,8 @@ pub fn app() {
                             .min_size(PreonVector::new(0, 48))
                             .expand_horizontally()
                             .receive_events(true)
+                            .hover_background_color(PreonColor::from_hex("#d4d4d4"))
+                            .transition(PreonAnimatable::BackgroundColor, Duration::from_millis(150), PreonEasing::EASE_OUT)
                             .id("first_panel")
                         .end()
                         .start_static_texture(&wood_man)
@@ -61,62 +68,90 @@ pub fn app() {
                         .start_label("Such art.".to_string())
                             .min_size(PreonVector::new(0, 200))
                             .expand_horizontally()
+                            .selectable(true)
                             .id("label")
                         .end()
                         .id("panel_list")
                     .end()
                 .end()
-                .start_panel_hex("#d3d3d3")
-                    .expand()
-                    .start_vbox()
+            .end()
+            .start_panel_hex("#d3d3d3")
+                .expand()
+                .start_vbox()
+                    .expand_horizontally()
+                    .start_label(format!("Size of PreonComponent: {}", std::mem::size_of::<PreonComponent>()))
                         .expand_horizontally()
-                        .start_label(format!("Size of PreonComponent: {}", std::mem::size_of::<PreonComponent>()))
-                            .expand_horizontally()
-                            .min_size(PreonVector::new(0, 48))
-                            .apply(Heading(&font_bold))
+                        .min_size(PreonVector::new(0, 48))
+                        .apply(Heading(&font_bold))
+                    .end()
+                    .start_text_input_str("")
+                        .placeholder("Rename the label, then press Return")
+                        .expand_horizontally()
+                        .margin(PreonBorder::from_xy(10, 0))
+                    .end()
+                    .start_text_area_str("")
+                        .placeholder("Notes")
+                        .max_height(160)
+                        .expand_horizontally()
+                        .margin(PreonBorder::new(10, 10, 0, 10))
+                    .end()
+                    .start_vbox()
+                        .background_color(PreonColor::from_hex("#da0037"))
+                        .foreground_color(PreonColor::WHITE)
+                        .margin(PreonBorder::from_single(10))
+                        .padding(PreonBorder::from_single(10))
+                        .start_label_str("Label 1").min_size(PreonVector::new(200, 20)).end()
+                        .start_label_str("Label 2").min_size(PreonVector::new(200, 20)).end()
+                        .start_label_str("Label 3").min_size(PreonVector::new(200, 20)).end()
+                        .start_label_str("Label 4").min_size(PreonVector::new(200, 20)).end()
+                        .start_label_str("Label 5").min_size(PreonVector::new(200, 20)).end()
+                        .start_hbox()
+                            .start_hbox()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
+                            .end()
+                            .start_hbox()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
+                            .end()
                         .end()
-                        .start_vbox()
-                            .background_color(PreonColor::from_hex("#da0037"))
-                            .foreground_color(PreonColor::WHITE)
-                            .margin(PreonBorder::from_single(10))
-                            .padding(PreonBorder::from_single(10))
-                            .start_label_str("Label 1").min_size(PreonVector::new(200, 20)).end()
-                            .start_label_str("Label 2").min_size(PreonVector::new(200, 20)).end()
-                            .start_label_str("Label 3").min_size(PreonVector::new(200, 20)).end()
-                            .start_label_str("Label 4").min_size(PreonVector::new(200, 20)).end()
-                            .start_label_str("Label 5").min_size(PreonVector::new(200, 20)).end()
+                        .start_hbox()
                             .start_hbox()
-                                .start_hbox()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                .end()
-                                .start_hbox()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                .end()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
                             .end()
                             .start_hbox()
-                                .start_hbox()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                .end()
-                                .start_hbox()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                    .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
-                                .end()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
+                                .start_label_str("Wowie").min_size(PreonVector::new(200, 20)).end()
                             .end()
                         .end()
                     .end()
                 .end()
             .end()
-        .build(),
-    );
+        .end()
+        .build_into(&mut engine, root);
 
-    preon::run(engine, move |tree, event, user_events| match event {
+    let first_panel = engine.find_by_id("first_panel").unwrap();
+    engine.on(first_panel, PreonEventKind::Pressed, |ctx| {
+        let mut rng = rand::thread_rng();
+        let first_panel = ctx.current_target();
+
+        ctx.engine.edit_style(first_panel, |style| {
+            style.background = PreonBackground::Color(PreonColor::from_rgba(
+                rng.gen(),
+                rng.gen(),
+                rng.gen(),
+                1.0,
+            ))
+        });
+    });
+
+    preon::run(engine, move |engine, event, _user_events| match event {
         PreonEvent::WindowOpened => {
             println!("Over the hills far away, Ferris came to play!");
 
-            tree.get_child_ref_mut_by_id("label").unwrap().text = "Poggers".to_string();
+            let label = engine.find_by_id("label").unwrap();
+            engine.set_text(label, "Poggers".to_string());
 
             // let list = tree.get_child_ref_mut_recursive(&panel_list);
             // let new_component = PreonComponentBuilder::new()
@@ -126,14 +161,24 @@ pub fn app() {
             // .build();
 
 
-            tree.get_child_ref_mut_by_id("first_panel").unwrap().style.background = PreonBackground::Color(
-                    PreonColor::from_rgba(rng.gen(), rng.gen(), rng.gen(), 1.0));
-
-            user_events.push(PreonUserEvent::ForceUpdate);
+            let first_panel = engine.find_by_id("first_panel").unwrap();
+            engine.edit_style(first_panel, |style| {
+                style.background = PreonBackground::Color(PreonColor::from_rgba(
+                    rng.gen(),
+                    rng.gen(),
+                    rng.gen(),
+                    1.0,
+                ))
+            });
         }
         PreonEvent::WindowResized(size) => {
-            tree.get_child_ref_mut_by_id("label").unwrap().text = format!("Size: {}", size);
-            // user_events.push(PreonUserEvent::ForceUpdate);
+            let label = engine.find_by_id("label").unwrap();
+            engine.set_text(label, format!("Size: {}", size));
+        }
+        PreonEvent::Submitted(input) => {
+            let text = engine.get_component(input).unwrap().text.clone();
+            let label = engine.find_by_id("label").unwrap();
+            engine.set_text(label, text);
         }
         PreonEvent::WindowClosed => println!("And they lived happily ever after..."),
         _ => {}
@@ -0,0 +1,139 @@
+//! Compares the generational [`PreonComponentStorage`] against the `HashMap<u64, PreonComponent>`
+//! it replaced, and full against incremental relayouts, on a tree of 11,111 components (10 children
+//! per component, 4 levels deep).
+//!
+//! Run with `cargo bench -p preon_engine`.
+
+#![feature(test)]
+
+extern crate test;
+
+use hashbrown::HashMap;
+use preon_engine::{
+    components::PreonComponent,
+    dirty,
+    events::{PreonEventEmitter, PreonUserEvent},
+    storage::PreonComponentStorage,
+    types::PreonVector,
+    PreonComponentHandle, PreonEngine,
+};
+use test::{black_box, Bencher};
+
+const BRANCHES: usize = 10;
+const DEPTH: usize = 4;
+
+fn build_engine() -> PreonEngine {
+    let mut engine = PreonEngine::new();
+    let mut level = vec![engine.root];
+
+    for _ in 0..DEPTH {
+        let mut next_level = Vec::with_capacity(level.len() * BRANCHES);
+
+        for &parent in level.iter() {
+            for _ in 0..BRANCHES {
+                let mut component = PreonComponent::new();
+                component.style.min_size = PreonVector::new(4, 4);
+                next_level.push(engine.push_component(parent, component));
+            }
+        }
+
+        level = next_level;
+    }
+
+    engine
+}
+
+/// The storage layout used before generational handles: components keyed by a plain `u64`.
+fn as_hashmap(storage: &PreonComponentStorage) -> HashMap<u64, PreonComponent> {
+    storage
+        .iter()
+        .map(|(handle, component)| (handle.index() as u64, component.clone()))
+        .collect()
+}
+
+fn handles(storage: &PreonComponentStorage) -> Vec<PreonComponentHandle> {
+    storage.iter().map(|(handle, _)| handle).collect()
+}
+
+#[bench]
+fn lookup_hashmap(b: &mut Bencher) {
+    let engine = build_engine();
+    let map = as_hashmap(&engine.components);
+    let keys: Vec<u64> = handles(&engine.components)
+        .iter()
+        .map(|h| h.index() as u64)
+        .collect();
+
+    b.iter(|| {
+        let mut total = 0;
+        for key in keys.iter() {
+            total += map.get(key).unwrap().children.len();
+        }
+        black_box(total)
+    });
+}
+
+#[bench]
+fn lookup_storage(b: &mut Bencher) {
+    let engine = build_engine();
+    let keys = handles(&engine.components);
+
+    b.iter(|| {
+        let mut total = 0;
+        for &key in keys.iter() {
+            total += engine.components.get(key).unwrap().children.len();
+        }
+        black_box(total)
+    });
+}
+
+fn resized_engine() -> (PreonEngine, PreonEventEmitter<PreonUserEvent>) {
+    let mut engine = build_engine();
+    let mut user_events = PreonEventEmitter::new();
+    user_events.push(PreonUserEvent::WindowResized(PreonVector::new(1920, 1080)));
+    user_events.flip();
+    engine.update(&user_events);
+
+    (engine, PreonEventEmitter::new())
+}
+
+#[bench]
+fn engine_relayout(b: &mut Bencher) {
+    let (mut engine, user_events) = resized_engine();
+    let root = engine.root;
+
+    b.iter(|| {
+        engine.mark_dirty(root, dirty::LAYOUT);
+        black_box(engine.update(&user_events))
+    });
+}
+
+/// Resizing a single leaf only lays out its ancestors and the siblings that moved.
+#[bench]
+fn engine_relayout_leaf(b: &mut Bencher) {
+    let (mut engine, user_events) = resized_engine();
+    let leaf = engine.depth_first(engine.root).last().unwrap();
+    let mut grow = false;
+
+    b.iter(|| {
+        grow = !grow;
+        engine.edit_style(leaf, |style| {
+            style.min_size = PreonVector::new(4, if grow { 8 } else { 4 })
+        });
+        black_box(engine.update(&user_events))
+    });
+}
+
+/// Paint-only changes skip layout entirely.
+#[bench]
+fn engine_repaint_text(b: &mut Bencher) {
+    let (mut engine, user_events) = resized_engine();
+    let leaf = engine.depth_first(engine.root).last().unwrap();
+    let mut count = 0;
+
+    b.iter(|| {
+        count += 1;
+        engine.set_text(leaf, count.to_string());
+        black_box(engine.update(&user_events))
+    });
+}
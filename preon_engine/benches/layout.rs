@@ -0,0 +1,63 @@
+//! Forced relayouts of single chains of nested components, 10, 20 and 40 levels deep. Every
+//! component is measured and arranged once per relayout, so the time should grow linearly with the
+//! depth.
+//!
+//! Run with `cargo bench -p preon_engine`.
+
+#![feature(test)]
+
+extern crate test;
+
+use preon_engine::{
+    components::PreonComponent,
+    dirty,
+    events::{PreonEventEmitter, PreonUserEvent},
+    size,
+    types::PreonVector,
+    PreonEngine,
+};
+use test::{black_box, Bencher};
+
+fn build_chain(depth: usize) -> (PreonEngine, PreonEventEmitter<PreonUserEvent>) {
+    let mut engine = PreonEngine::new();
+    let mut parent = engine.root;
+
+    for _ in 0..depth {
+        let mut component = PreonComponent::new();
+        component.style.min_size = PreonVector::new(4, 4);
+        component.style.size_flags = size::FIT | size::EXPAND;
+        parent = engine.push_component(parent, component);
+    }
+
+    let mut user_events = PreonEventEmitter::new();
+    user_events.push(PreonUserEvent::WindowResized(PreonVector::new(1920, 1080)));
+    user_events.flip();
+    engine.update(&user_events);
+
+    (engine, PreonEventEmitter::new())
+}
+
+fn relayout(b: &mut Bencher, depth: usize) {
+    let (mut engine, user_events) = build_chain(depth);
+    let root = engine.root;
+
+    b.iter(|| {
+        engine.mark_dirty(root, dirty::LAYOUT);
+        black_box(engine.update(&user_events))
+    });
+}
+
+#[bench]
+fn relayout_depth_10(b: &mut Bencher) {
+    relayout(b, 10);
+}
+
+#[bench]
+fn relayout_depth_20(b: &mut Bencher) {
+    relayout(b, 20);
+}
+
+#[bench]
+fn relayout_depth_40(b: &mut Bencher) {
+    relayout(b, 40);
+}
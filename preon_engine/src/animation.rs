@@ -0,0 +1,414 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::types::{PreonBorder, PreonColor, PreonCorners, PreonVector};
+
+/// How an animation moves from one value to the next over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreonEasing {
+    Linear,
+    /// Same as the CSS `cubic-bezier(x1, y1, x2, y2)` timing function.
+    CubicBezier(f32, f32, f32, f32),
+    /// A damped spring with a mass of 1, evaluated in seconds. It can overshoot, and snaps to
+    /// the end value once the animation is over, so make sure it has settled by then.
+    Spring { stiffness: f32, damping: f32 },
+}
+
+impl PreonEasing {
+    pub const EASE: PreonEasing = PreonEasing::CubicBezier(0.25, 0.1, 0.25, 1.0);
+    pub const EASE_IN: PreonEasing = PreonEasing::CubicBezier(0.42, 0.0, 1.0, 1.0);
+    pub const EASE_OUT: PreonEasing = PreonEasing::CubicBezier(0.0, 0.0, 0.58, 1.0);
+    pub const EASE_IN_OUT: PreonEasing = PreonEasing::CubicBezier(0.42, 0.0, 0.58, 1.0);
+
+    /// Maps `progress` (0 to 1) of an animation lasting `duration` to how far the value should
+    /// have moved, 0 being the start value and 1 the end value.
+    pub fn ease(&self, progress: f32, duration: Duration) -> f32 {
+        if progress <= 0.0 {
+            return 0.0;
+        } else if progress >= 1.0 {
+            return 1.0;
+        }
+
+        match *self {
+            PreonEasing::Linear => progress,
+            PreonEasing::CubicBezier(x1, y1, x2, y2) => {
+                let t = solve_bezier(progress, x1, x2);
+                bezier(t, y1, y2)
+            }
+            PreonEasing::Spring { stiffness, damping } => {
+                let time = progress * duration.as_secs_f32();
+                let omega = libm::sqrtf(stiffness.max(0.0));
+                let zeta = damping / (2.0 * omega.max(f32::EPSILON));
+
+                if zeta < 1.0 {
+                    let damped = omega * libm::sqrtf(1.0 - zeta * zeta);
+                    let decay = libm::expf(-zeta * omega * time);
+
+                    1.0 - decay
+                        * (libm::cosf(damped * time)
+                            + zeta * omega / damped * libm::sinf(damped * time))
+                } else {
+                    // Critically damped, overdamped springs are treated the same
+                    1.0 - libm::expf(-omega * time) * (1.0 + omega * time)
+                }
+            }
+        }
+    }
+}
+
+impl Default for PreonEasing {
+    fn default() -> Self {
+        PreonEasing::EASE
+    }
+}
+
+/// One coordinate of a cubic bezier curve from 0 to 1, with control points `a` and `b`.
+fn bezier(t: f32, a: f32, b: f32) -> f32 {
+    let inverse = 1.0 - t;
+    3.0 * inverse * inverse * t * a + 3.0 * inverse * t * t * b + t * t * t
+}
+
+/// Finds `t` for which the x coordinate of the curve is `x`.
+fn solve_bezier(x: f32, x1: f32, x2: f32) -> f32 {
+    // Newton's method converges quickly for most curves
+    let mut t = x;
+    for _ in 0..8 {
+        let error = bezier(t, x1, x2) - x;
+        if crate::abs!(error) < 1e-5 {
+            return t;
+        }
+
+        let inverse = 1.0 - t;
+        let slope = 3.0 * inverse * inverse * x1 + 6.0 * inverse * t * (x2 - x1) + 3.0 * t * t * (1.0 - x2);
+        if crate::abs!(slope) < 1e-6 {
+            break;
+        }
+
+        t -= error / slope;
+    }
+
+    // Fall back to bisection for steep curves
+    let (mut low, mut high) = (0.0, 1.0);
+    t = x;
+    for _ in 0..32 {
+        let value = bezier(t, x1, x2);
+        if crate::abs!(value - x) < 1e-5 {
+            break;
+        }
+
+        if value < x {
+            low = t;
+        } else {
+            high = t;
+        }
+
+        t = (low + high) * 0.5;
+    }
+
+    t
+}
+
+/// The style properties that can be animated, see [`PreonTransition`] and [`PreonKeyframes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreonAnimatable {
+    /// Includes the hover and focus backgrounds. Images aren't animated, a missing background
+    /// fades in and out as a transparent color.
+    BackgroundColor,
+    /// Includes the hover and focus colors.
+    ForegroundColor,
+    MinSize,
+    Margin,
+    Padding,
+    CornerRadius,
+    /// Applies to the component and all of its descendants.
+    Opacity,
+}
+
+/// A value of a [`PreonAnimatable`] property.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreonAnimationValue {
+    /// For [`PreonAnimatable::BackgroundColor`] and [`PreonAnimatable::ForegroundColor`].
+    Color(PreonColor),
+    /// For [`PreonAnimatable::MinSize`].
+    Size(PreonVector<i32>),
+    /// For [`PreonAnimatable::Margin`] and [`PreonAnimatable::Padding`].
+    Border(PreonBorder),
+    /// For [`PreonAnimatable::CornerRadius`].
+    Corners(PreonCorners),
+    /// For [`PreonAnimatable::Opacity`].
+    Number(f32),
+}
+
+impl PreonAnimationValue {
+    /// Interpolates between `self` (at 0) and `other` (at 1), `t` can go beyond that for easings
+    /// that overshoot. Values of different kinds jump to `other` halfway.
+    pub fn lerp(&self, other: &PreonAnimationValue, t: f32) -> PreonAnimationValue {
+        fn lerp(a: f32, b: f32, t: f32) -> f32 {
+            a + (b - a) * t
+        }
+
+        fn lerp_i32(a: i32, b: i32, t: f32) -> i32 {
+            libm::roundf(lerp(a as f32, b as f32, t)) as i32
+        }
+
+        use PreonAnimationValue::*;
+
+        match (*self, *other) {
+            (Color(a), Color(b)) => {
+                // Fully transparent colors take the hue of the other color, so fading in or out
+                // doesn't pass through black
+                let (a_rgb, b_rgb) = match (a.a <= 0.0, b.a <= 0.0) {
+                    (true, false) => (b, b),
+                    (false, true) => (a, a),
+                    _ => (a, b),
+                };
+
+                // Already in linear color space, `PreonColor::from_rgba` would convert it again
+                Color(PreonColor {
+                    r: lerp(a_rgb.r, b_rgb.r, t),
+                    g: lerp(a_rgb.g, b_rgb.g, t),
+                    b: lerp(a_rgb.b, b_rgb.b, t),
+                    a: lerp(a.a, b.a, t).clamp(0.0, 1.0),
+                })
+            }
+            (Size(a), Size(b)) => Size(PreonVector::new(
+                lerp_i32(a.x, b.x, t),
+                lerp_i32(a.y, b.y, t),
+            )),
+            (Border(a), Border(b)) => Border(PreonBorder::new(
+                lerp_i32(a.top, b.top, t),
+                lerp_i32(a.right, b.right, t),
+                lerp_i32(a.bottom, b.bottom, t),
+                lerp_i32(a.left, b.left, t),
+            )),
+            (Corners(a), Corners(b)) => Corners(PreonCorners::new(
+                lerp(a.top_left, b.top_left, t).max(0.0),
+                lerp(a.top_right, b.top_right, t).max(0.0),
+                lerp(a.bottom_right, b.bottom_right, t).max(0.0),
+                lerp(a.bottom_left, b.bottom_left, t).max(0.0),
+            )),
+            (Number(a), Number(b)) => Number(lerp(a, b, t)),
+            _ if t < 0.5 => *self,
+            _ => *other,
+        }
+    }
+}
+
+/// Animates a property whenever its value in the style changes, see
+/// [`PreonComponentBuilderStyleExtension::transition`](crate::style::PreonComponentBuilderStyleExtension::transition).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreonTransition {
+    pub property: PreonAnimatable,
+    pub duration: Duration,
+    pub easing: PreonEasing,
+    /// Time before the transition starts.
+    pub delay: Duration,
+}
+
+impl PreonTransition {
+    pub fn new(property: PreonAnimatable, duration: Duration, easing: PreonEasing) -> Self {
+        Self {
+            property,
+            duration,
+            easing,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+/// A value the animated property passes through, see [`PreonKeyframes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreonKeyframe {
+    /// From 0 (start of the animation) to 1 (end of the animation).
+    pub offset: f32,
+    pub value: PreonAnimationValue,
+    /// Used on the way to the next keyframe.
+    pub easing: PreonEasing,
+}
+
+/// A sequence of values for a single property, started with
+/// [`PreonEngine::animate`](crate::PreonEngine::animate). Without a frame at offset 0 or 1, the
+/// animation starts or ends at the value from the style, which the property returns to once all
+/// iterations are done.
+///
+/// ```ignore
+/// engine.animate(
+///     badge,
+///     PreonKeyframes::new(PreonAnimatable::Opacity, Duration::from_millis(800))
+///         .frame(0.0, PreonAnimationValue::Number(1.0), PreonEasing::EASE_IN_OUT)
+///         .frame(0.5, PreonAnimationValue::Number(0.3), PreonEasing::EASE_IN_OUT)
+///         .frame(1.0, PreonAnimationValue::Number(1.0), PreonEasing::Linear)
+///         .forever(),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreonKeyframes {
+    pub property: PreonAnimatable,
+    /// Sorted by offset.
+    pub frames: Vec<PreonKeyframe>,
+    /// The length of a single iteration.
+    pub duration: Duration,
+    /// `None` repeats forever.
+    pub iterations: Option<u32>,
+    /// Every other iteration runs backwards.
+    pub alternate: bool,
+}
+
+impl PreonKeyframes {
+    pub fn new(property: PreonAnimatable, duration: Duration) -> Self {
+        Self {
+            property,
+            frames: Vec::new(),
+            duration,
+            iterations: Some(1),
+            alternate: false,
+        }
+    }
+
+    /// Adds a keyframe, keeping the frames sorted by offset.
+    pub fn frame(mut self, offset: f32, value: PreonAnimationValue, easing: PreonEasing) -> Self {
+        let index = self.frames.partition_point(|frame| frame.offset <= offset);
+        self.frames.insert(index, PreonKeyframe { offset, value, easing });
+        self
+    }
+
+    pub fn repeat(mut self, iterations: u32) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    pub fn forever(mut self) -> Self {
+        self.iterations = None;
+        self
+    }
+
+    pub fn alternate(mut self) -> Self {
+        self.alternate = true;
+        self
+    }
+
+    /// The value at `progress` (0 to 1) through a single iteration, `target` being the value
+    /// from the style.
+    fn sample(&self, progress: f32, target: &PreonAnimationValue) -> PreonAnimationValue {
+        let implicit = |offset| PreonKeyframe {
+            offset,
+            value: *target,
+            easing: PreonEasing::default(),
+        };
+
+        let next = self.frames.partition_point(|frame| frame.offset <= progress);
+        let from = match next.checked_sub(1) {
+            Some(index) => self.frames[index],
+            None => implicit(0.0),
+        };
+        let to = match self.frames.get(next) {
+            Some(&frame) => frame,
+            None => implicit(1.0),
+        };
+
+        let length = to.offset - from.offset;
+        if length <= 0.0 {
+            return from.value;
+        }
+
+        let t = from
+            .easing
+            .ease((progress - from.offset) / length, self.duration.mul_f32(length));
+
+        from.value.lerp(&to.value, t)
+    }
+}
+
+/// A running animation of a single property.
+#[derive(Debug, Clone)]
+pub(crate) enum PreonAnimation {
+    Transition {
+        from: PreonAnimationValue,
+        transition: PreonTransition,
+        start: Duration,
+    },
+    Keyframes {
+        keyframes: PreonKeyframes,
+        start: Duration,
+    },
+}
+
+impl PreonAnimation {
+    /// The value at engine time `time`, `None` once the animation is over.
+    pub(crate) fn sample(&self, time: Duration, target: &PreonAnimationValue) -> Option<PreonAnimationValue> {
+        match *self {
+            PreonAnimation::Transition {
+                ref from,
+                ref transition,
+                start,
+            } => {
+                let elapsed = time.saturating_sub(start + transition.delay);
+                if elapsed >= transition.duration {
+                    return None;
+                }
+
+                let progress = elapsed.as_secs_f32() / transition.duration.as_secs_f32();
+                let t = transition.easing.ease(progress, transition.duration);
+
+                Some(from.lerp(target, t))
+            }
+            PreonAnimation::Keyframes {
+                ref keyframes,
+                start,
+            } => {
+                if keyframes.duration.is_zero() {
+                    return None;
+                }
+
+                let elapsed = time.saturating_sub(start).as_secs_f32();
+                let iteration = elapsed / keyframes.duration.as_secs_f32();
+                let index = iteration as u32;
+
+                if matches!(keyframes.iterations, Some(iterations) if index >= iterations) {
+                    return None;
+                }
+
+                let progress = iteration - index as f32;
+                let progress = if keyframes.alternate && index % 2 == 1 {
+                    1.0 - progress
+                } else {
+                    progress
+                };
+
+                Some(keyframes.sample(progress, target))
+            }
+        }
+    }
+}
+
+/// An animated property of a component, see [`PreonAnimationState`].
+#[derive(Debug, Clone)]
+pub(crate) struct PreonAnimatedValue {
+    pub(crate) property: PreonAnimatable,
+    /// The value of the property in the style the last time it was checked, transitions start
+    /// when it changes.
+    pub(crate) target: PreonAnimationValue,
+    /// Used instead of the style while `animation` runs.
+    pub(crate) current: PreonAnimationValue,
+    pub(crate) animation: Option<PreonAnimation>,
+}
+
+/// Transitions and animations of a component, managed by the engine.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PreonAnimationState {
+    pub(crate) values: Vec<PreonAnimatedValue>,
+}
+
+impl PreonAnimationState {
+    /// The animated value of `property`, `None` if it isn't animating.
+    pub(crate) fn get(&self, property: PreonAnimatable) -> Option<PreonAnimationValue> {
+        self.values
+            .iter()
+            .find(|value| value.property == property && value.animation.is_some())
+            .map(|value| value.current)
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.values.iter().any(|value| value.animation.is_some())
+    }
+}
@@ -0,0 +1,35 @@
+use alloc::string::String;
+
+/// Where text inputs and selectable labels copy text to and paste it from. Render modules should
+/// provide one using the clipboard of the system, see
+/// [`PreonEngine::set_clipboard`](crate::PreonEngine::set_clipboard).
+pub trait PreonClipboard {
+    /// The text on the clipboard, `None` if it's empty or holds something else.
+    fn get_text(&mut self) -> Option<String>;
+
+    /// Replaces the contents of the clipboard with `text`.
+    fn set_text(&mut self, text: String);
+}
+
+/// Keeps copied text to itself, used until a render module sets the clipboard of the system. Also
+/// useful without a window, e.g. in tests.
+#[derive(Debug, Clone, Default)]
+pub struct PreonMemoryClipboard {
+    text: Option<String>,
+}
+
+impl PreonMemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PreonClipboard for PreonMemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+    }
+}
@@ -2,9 +2,14 @@ use core::str::FromStr;
 
 use alloc::string::String;
 
-use crate::{components::PreonComponent, style::{PreonStyle, PreonBackground, PreonTextStyle}, layout::PreonLayout, types::{PreonColor, PreonAlignment}};
- 
-use super::{PreonComponentBuilder, vbox::PreonComponentBuilderVBoxExtension};
+use crate::{
+    components::PreonComponent,
+    layout::PreonLayout,
+    style::{PreonBackground, PreonStateStyle, PreonStyle, PreonTextStyle},
+    types::{PreonAlignment, PreonColor},
+};
+
+use super::{vbox::PreonComponentBuilderVBoxExtension, PreonComponentBuilder};
 
 pub trait PreonComponentBuilderButtonExtension {
     fn start_button(&mut self, text: String) -> &mut PreonComponentBuilder;
@@ -20,6 +25,18 @@ impl PreonComponentBuilderButtonExtension for PreonComponentBuilder {
             style: PreonStyle {
                 layout: PreonLayout::Container,
                 background: PreonBackground::Color(PreonColor::from_rgba8(0x37, 0x63, 0xF2, 0xFF)),
+                hover: PreonStateStyle {
+                    background: Some(PreonBackground::Color(PreonColor::from_rgba8(
+                        0x55, 0x7B, 0xF5, 0xFF,
+                    ))),
+                    ..Default::default()
+                },
+                focus: PreonStateStyle {
+                    background: Some(PreonBackground::Color(PreonColor::from_rgba8(
+                        0x24, 0x4E, 0xD8, 0xFF,
+                    ))),
+                    ..Default::default()
+                },
                 foreground_color: PreonColor::WHITE,
                 text_style: PreonTextStyle {
                     vertical_align: PreonAlignment::Center,
@@ -29,6 +46,7 @@ impl PreonComponentBuilderButtonExtension for PreonComponentBuilder {
                 ..self.inherited_style()
             },
             mouse_events: true,
+            focusable: true,
             ..Default::default()
         });
 
@@ -46,4 +64,4 @@ impl PreonComponentBuilderButtonExtension for PreonComponentBuilder {
     fn empty_button_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder {
         self.start_button_str(text).end()
     }
-}
\ No newline at end of file
+}
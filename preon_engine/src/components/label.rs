@@ -1,16 +1,19 @@
 use core::str::FromStr;
 
-use alloc::string::String;
+use alloc::{boxed::Box, string::String};
 
 use crate::components::PreonComponent;
  
-use super::PreonComponentBuilder;
+use super::{text_input::PreonTextInputState, PreonComponentBuilder};
 
 pub trait PreonComponentBuilderLabelExtension {
     fn start_label(&mut self, text: String) -> &mut PreonComponentBuilder;
     fn start_label_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder;
     fn empty_label(&mut self, text: String) -> &mut PreonComponentBuilder;
     fn empty_label_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder;
+    /// Lets the text be selected with the mouse (and the keys of a text input while it's focused
+    /// by clicking it), and copied with Ctrl+C. The text wraps inside of the content width.
+    fn selectable(&mut self, selectable: bool) -> &mut PreonComponentBuilder;
 }
 
 impl PreonComponentBuilderLabelExtension for PreonComponentBuilder {
@@ -35,4 +38,56 @@ impl PreonComponentBuilderLabelExtension for PreonComponentBuilder {
     fn empty_label_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder {
         self.start_label_str(text).end()
     }
-}
\ No newline at end of file
+
+    fn selectable(&mut self, selectable: bool) -> &mut PreonComponentBuilder {
+        let component = self.current_mut();
+
+        if component.is_text_input() {
+            log::warn!("selectable() only works on labels");
+        } else if selectable {
+            if component.text_input.is_none() {
+                component.text_input = Some(Box::new(PreonTextInputState {
+                    multiline: true,
+                    read_only: true,
+                    enabled_mouse_events: !component.mouse_events,
+                    ..Default::default()
+                }));
+                component.mouse_events = true;
+            }
+        } else if let Some(state) = component.text_input.take() {
+            // Labels receiving mouse events on their own keep them
+            if state.enabled_mouse_events {
+                component.mouse_events = false;
+            }
+        }
+
+        self
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::PreonComponentBuilderLabelExtension;
+    use crate::components::PreonComponentBuilder;
+
+    /// Whether a label that had `mouse_events` set to `initial` receives mouse events after
+    /// `selectable()` was called with each of `calls`.
+    fn mouse_events_after(initial: bool, calls: &[bool]) -> bool {
+        let mut builder = PreonComponentBuilder::new();
+        builder.start_label_str("Hello").current_mut().mouse_events = initial;
+
+        for &selectable in calls {
+            builder.selectable(selectable);
+        }
+
+        builder.build().mouse_events
+    }
+
+    #[test]
+    fn selectable_restores_mouse_events() {
+        assert!(mouse_events_after(false, &[true]));
+        assert!(!mouse_events_after(false, &[true, false]));
+        assert!(!mouse_events_after(false, &[true, true, false]));
+        assert!(mouse_events_after(true, &[true, false]));
+        assert!(!mouse_events_after(false, &[false]));
+    }
+}
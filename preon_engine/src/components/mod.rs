@@ -1,30 +1,35 @@
 use alloc::{
     borrow::ToOwned,
+    boxed::Box,
     string::{String, ToString},
     vec,
     vec::Vec,
 };
 use core::fmt::Debug;
+use hashbrown::HashMap;
 
 use crate::{
+    animation::{PreonAnimatable, PreonAnimationState, PreonAnimationValue},
     events::{PreonButtonState, PreonEvent},
-    layout::{
-        columns::PreonColumnsLayoutProvider, container::PreonContainerLayoutProvider,
-        rows::PreonRowsLayoutProvider, PreonLayout,
-    },
-    rendering::{PreonRenderPass, PreonShape},
+    rendering::PreonShape,
     style::{PreonBackground, PreonStyle},
-    types::{PreonAlignment, PreonColor, PreonRect, PreonVector},
-    PreonComponentHandle,
+    types::{PreonAlignment, PreonBorder, PreonColor, PreonCorners, PreonRect, PreonVector},
+    dirty, PreonComponentHandle, PreonEngine,
 };
 
 pub mod button;
 pub mod hbox;
 pub mod label;
 pub mod panel;
+pub mod scroll_view;
 pub mod static_texture;
+pub mod text_area;
+pub mod text_input;
 pub mod vbox;
 
+/// Half the extent of [`PreonComponent::get_clip_rect`] along an axis that isn't clipped.
+const UNCLIPPED: i32 = 1 << 24;
+
 #[repr(transparent)]
 #[derive(Clone)]
 pub struct ExcludeFromDebug<T: Clone>(T);
@@ -40,11 +45,37 @@ impl<T: Clone> core::fmt::Debug for ExcludeFromDebug<T> {
 pub struct PreonComponent {
     pub parent: Option<PreonComponentHandle>,
     pub children: Vec<PreonComponentHandle>,
+    /// Unique name, indexed by the engine. Use [`PreonEngine::set_id`](crate::PreonEngine::set_id) to change it
+    pub(crate) id: Option<String>,
+    pub classes: Vec<String>,
     pub style: PreonStyle,
+    /// Change it with [`PreonEngine::set_text`](crate::PreonEngine::set_text), which also resets the
+    /// caret and undo history of text inputs.
     pub text: String,
     pub inner_size: PreonVector<i32>,
     pub inner_position: PreonVector<i32>,
     pub mouse_events: bool,
+    /// Can receive keyboard focus, see [`PreonEngine::focus`](crate::PreonEngine::focus)
+    pub focusable: bool,
+    /// Components with a tab index are focused first when pressing Tab (lowest first), the
+    /// rest follows in tree order. See [`PreonEngine::focus_order`](crate::PreonEngine::focus_order)
+    pub tab_index: Option<u32>,
+    /// See [`dirty`](crate::dirty), use [`PreonEngine::mark_dirty`](crate::PreonEngine::mark_dirty) to change it
+    pub(crate) dirty: u8,
+    /// Managed by the engine, see [`PreonComponent::is_hovered`]
+    pub(crate) hovered: bool,
+    /// Managed by the engine, see [`PreonComponent::is_focused`]
+    pub(crate) focused: bool,
+    /// Set for scroll views, see [`PreonComponent::is_scroll_view`]
+    pub(crate) scroll: Option<Box<scroll_view::PreonScrollState>>,
+    /// Managed by the engine, see [`PreonComponent::is_overflowing_horizontally`]
+    pub(crate) overflowing_x: bool,
+    /// Managed by the engine, see [`PreonComponent::is_overflowing_vertically`]
+    pub(crate) overflowing_y: bool,
+    /// Set for components with transitions or animations, see [`PreonComponent::is_animating`]
+    pub(crate) animation: Option<Box<PreonAnimationState>>,
+    /// Set for text inputs and selectable labels, see [`PreonComponent::is_selectable`]
+    pub(crate) text_input: Option<Box<text_input::PreonTextInputState>>,
 }
 
 impl PreonComponent {
@@ -52,22 +83,187 @@ impl PreonComponent {
         PreonComponent {
             parent: None,
             children: Vec::new(),
+            id: None,
+            classes: Vec::new(),
             style: PreonStyle::default(),
             text: String::new(),
             inner_size: PreonVector::zero(),
             inner_position: PreonVector::zero(),
             mouse_events: false,
+            focusable: false,
+            tab_index: None,
+            dirty: dirty::LAYOUT,
+            hovered: false,
+            focused: false,
+            scroll: None,
+            overflowing_x: false,
+            overflowing_y: false,
+            animation: None,
+            text_input: None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns true if the mouse is over this component, or over one of its descendants. Only
+    /// components receiving mouse events can be hovered.
+    #[inline(always)]
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// Returns true if this component receives keyboard input, see [`PreonEngine::focused`](crate::PreonEngine::focused).
+    #[inline(always)]
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Returns true if a child sticks out of the content rect on the left or right, whether it's
+    /// clipped or not. See [`PreonEvent::OverflowChanged`].
+    #[inline(always)]
+    pub fn is_overflowing_horizontally(&self) -> bool {
+        self.overflowing_x
+    }
+
+    /// Returns true if a child sticks out of the content rect at the top or bottom, whether it's
+    /// clipped or not. See [`PreonEvent::OverflowChanged`].
+    #[inline(always)]
+    pub fn is_overflowing_vertically(&self) -> bool {
+        self.overflowing_y
+    }
+
+    /// Returns true while a transition or keyframe animation is running, see
+    /// [`PreonEngine::animate`](crate::PreonEngine::animate).
+    pub fn is_animating(&self) -> bool {
+        match self.animation {
+            Some(ref animation) => animation.is_running(),
+            None => false,
         }
     }
 
+    /// The animated value of `property`, `None` if it isn't animating.
+    #[inline(always)]
+    fn animated(&self, property: PreonAnimatable) -> Option<PreonAnimationValue> {
+        match self.animation {
+            Some(ref animation) => animation.get(property),
+            None => None,
+        }
+    }
+
+    /// The margin in use, which differs from [`PreonStyle::margin`] while it's animating.
+    #[inline(always)]
+    pub fn get_margin(&self) -> PreonBorder {
+        match self.animated(PreonAnimatable::Margin) {
+            Some(PreonAnimationValue::Border(margin)) => margin,
+            _ => self.style.margin,
+        }
+    }
+
+    /// The padding in use, which differs from [`PreonStyle::padding`] while it's animating.
+    #[inline(always)]
+    pub fn get_padding(&self) -> PreonBorder {
+        match self.animated(PreonAnimatable::Padding) {
+            Some(PreonAnimationValue::Border(padding)) => padding,
+            _ => self.style.padding,
+        }
+    }
+
+    /// The minimum size in use, which differs from [`PreonStyle::min_size`] while it's animating, and
+    /// for text areas growing with their text.
+    #[inline(always)]
+    pub fn get_min_size(&self) -> PreonVector<i32> {
+        let min_size = match self.animated(PreonAnimatable::MinSize) {
+            Some(PreonAnimationValue::Size(min_size)) => min_size,
+            _ => self.style.min_size,
+        };
+
+        match self.text_input.as_ref().and_then(|state| state.grow_height(self.get_padding())) {
+            Some(height) => PreonVector::new(min_size.x, min_size.y.max(height)),
+            None => min_size,
+        }
+    }
+
+    /// The corner radius in use, which differs from [`PreonStyle::corner_radius`] while it's animating.
+    #[inline(always)]
+    pub fn get_corner_radius(&self) -> PreonCorners {
+        match self.animated(PreonAnimatable::CornerRadius) {
+            Some(PreonAnimationValue::Corners(radius)) => radius,
+            _ => self.style.corner_radius,
+        }
+    }
+
+    /// The opacity in use, which differs from [`PreonStyle::opacity`] while it's animating.
+    /// Doesn't include the opacity of the ancestors.
+    #[inline(always)]
+    pub fn get_opacity(&self) -> f32 {
+        match self.animated(PreonAnimatable::Opacity) {
+            Some(PreonAnimationValue::Number(opacity)) => opacity,
+            _ => self.style.opacity,
+        }
+    }
+
+    /// The background from the style, with the hover and focus overrides applied.
+    pub fn get_background(&self) -> &PreonBackground {
+        match (&self.style.hover.background, &self.style.focus.background) {
+            (Some(background), _) if self.hovered => background,
+            (_, Some(background)) if self.focused => background,
+            _ => &self.style.background,
+        }
+    }
+
+    /// The foreground color in use, with the hover and focus overrides applied.
+    pub fn get_foreground_color(&self) -> PreonColor {
+        match self.animated(PreonAnimatable::ForegroundColor) {
+            Some(PreonAnimationValue::Color(color)) => color,
+            _ => self.foreground_color_target(),
+        }
+    }
+
+    fn foreground_color_target(&self) -> PreonColor {
+        match (self.style.hover.foreground_color, self.style.focus.foreground_color) {
+            (Some(color), _) if self.hovered => color,
+            (_, Some(color)) if self.focused => color,
+            _ => self.style.foreground_color,
+        }
+    }
+
+    /// The value of `property` according to the style, transitions animate towards it.
+    pub(crate) fn animation_target(&self, property: PreonAnimatable) -> PreonAnimationValue {
+        match property {
+            PreonAnimatable::BackgroundColor => PreonAnimationValue::Color(match *self.get_background() {
+                PreonBackground::Color(color) => color,
+                _ => PreonColor::TRANSPARENT_BLACK,
+            }),
+            PreonAnimatable::ForegroundColor => PreonAnimationValue::Color(self.foreground_color_target()),
+            PreonAnimatable::MinSize => PreonAnimationValue::Size(self.style.min_size),
+            PreonAnimatable::Margin => PreonAnimationValue::Border(self.style.margin),
+            PreonAnimatable::Padding => PreonAnimationValue::Border(self.style.padding),
+            PreonAnimatable::CornerRadius => PreonAnimationValue::Corners(self.style.corner_radius),
+            PreonAnimatable::Opacity => PreonAnimationValue::Number(self.style.opacity),
+        }
+    }
+
+    #[inline(always)]
+    pub fn dirty(&self) -> u8 {
+        self.dirty
+    }
+
+    #[inline(always)]
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+
     #[inline(always)]
     pub fn set_content_position(&mut self, new_position: PreonVector<i32>) {
-        self.inner_position = new_position - self.style.padding.top_left();
+        self.inner_position = new_position - self.get_padding().top_left();
     }
 
     #[inline(always)]
     pub fn get_content_position(&self) -> PreonVector<i32> {
-        self.inner_position + self.style.padding.top_left()
+        self.inner_position + self.get_padding().top_left()
     }
 
     #[inline(always)]
@@ -108,12 +304,12 @@ impl PreonComponent {
     #[inline(always)]
     pub fn set_outer_position(&mut self, new_position: PreonVector<i32>) {
         self.inner_position =
-            new_position + self.style.border.top_left() + self.style.margin.top_left();
+            new_position + self.style.border.top_left() + self.get_margin().top_left();
     }
 
     #[inline(always)]
     pub fn get_outer_position(&self) -> PreonVector<i32> {
-        self.inner_position - self.style.border.top_left() - self.style.margin.top_left()
+        self.inner_position - self.style.border.top_left() - self.get_margin().top_left()
     }
 
     #[inline(always)]
@@ -123,12 +319,12 @@ impl PreonComponent {
 
     #[inline(always)]
     pub fn set_content_size(&mut self, new_size: PreonVector<i32>) {
-        self.inner_size = new_size + self.style.padding;
+        self.inner_size = new_size + self.get_padding();
     }
 
     #[inline(always)]
     pub fn get_content_size(&self) -> PreonVector<i32> {
-        self.get_inner_size() - self.style.padding
+        self.get_inner_size() - self.get_padding()
     }
 
     #[inline(always)]
@@ -139,8 +335,8 @@ impl PreonComponent {
     #[inline(always)]
     pub fn get_inner_size(&self) -> PreonVector<i32> {
         PreonVector::new(
-            self.inner_size.x.max(self.style.min_size.x),
-            self.inner_size.y.max(self.style.min_size.y),
+            self.inner_size.x.max(self.get_min_size().x),
+            self.inner_size.y.max(self.get_min_size().y),
         )
     }
 
@@ -156,17 +352,17 @@ impl PreonComponent {
 
     #[inline(always)]
     pub fn set_outer_size(&mut self, new_size: PreonVector<i32>) {
-        self.inner_size = new_size - self.style.margin - self.style.border;
+        self.inner_size = new_size - self.get_margin() - self.style.border;
     }
 
     #[inline(always)]
     pub fn get_outer_size(&self) -> PreonVector<i32> {
-        self.get_inner_size() + self.style.border + self.style.margin
+        self.get_inner_size() + self.style.border + self.get_margin()
     }
 
     #[inline(always)]
     pub fn set_content_size_x(&mut self, new_x: i32) {
-        self.set_inner_size_x(new_x + self.style.padding.x());
+        self.set_inner_size_x(new_x + self.get_padding().x());
     }
 
     #[inline(always)]
@@ -176,17 +372,17 @@ impl PreonComponent {
 
     #[inline(always)]
     pub fn set_border_size_x(&mut self, new_x: i32) {
-        self.set_inner_size_x(new_x - self.style.margin.x());
+        self.set_inner_size_x(new_x - self.get_margin().x());
     }
 
     #[inline(always)]
     pub fn set_outer_size_x(&mut self, new_x: i32) {
-        self.set_inner_size_x(new_x - self.style.margin.x() - self.style.border.x());
+        self.set_inner_size_x(new_x - self.get_margin().x() - self.style.border.x());
     }
 
     #[inline(always)]
     pub fn set_content_size_y(&mut self, new_y: i32) {
-        self.set_inner_size_y(new_y + self.style.padding.y());
+        self.set_inner_size_y(new_y + self.get_padding().y());
     }
 
     #[inline(always)]
@@ -196,33 +392,61 @@ impl PreonComponent {
 
     #[inline(always)]
     pub fn set_border_size_y(&mut self, new_y: i32) {
-        self.set_inner_size_y(new_y - self.style.margin.y());
+        self.set_inner_size_y(new_y - self.get_margin().y());
     }
 
     #[inline(always)]
     pub fn set_outer_size_y(&mut self, new_y: i32) {
-        self.set_inner_size_y(new_y - self.style.margin.y() - self.style.border.y());
+        self.set_inner_size_y(new_y - self.get_margin().y() - self.style.border.y());
     }
 
-    pub(crate) fn layout(&mut self) {
-        use crate::layout::PreonLayoutProvider;
+    /// The area the text and children of this component are clipped to, `None` if nothing is
+    /// clipped. See [`PreonOverflow`], scroll views clip to their content rect. Along an axis
+    /// with visible overflow the clip reaches far beyond the component.
+    pub fn get_clip_rect(&self) -> Option<PreonRect<i32>> {
+        let clip_x = self.style.overflow_x.is_clipped();
+        let clip_y = self.style.overflow_y.is_clipped();
 
-        for &child in self.children.iter() {
-            child.layout();
+        if !clip_x && !clip_y {
+            return None;
         }
 
-        match self.style.layout {
-            PreonLayout::Rows => PreonRowsLayoutProvider::layout(self),
-            PreonLayout::Columns => PreonColumnsLayoutProvider::layout(self),
-            PreonLayout::Container => PreonContainerLayoutProvider::layout(self),
+        let rect = if self.is_scroll_view() {
+            self.get_content_rect()
+        } else {
+            self.get_inner_rect()
+        };
+
+        let (x, width) = if clip_x { (rect.position.x, rect.size.x) } else { (-UNCLIPPED, UNCLIPPED * 2) };
+        let (y, height) = if clip_y { (rect.position.y, rect.size.y) } else { (-UNCLIPPED, UNCLIPPED * 2) };
+
+        Some(PreonRect::new(PreonVector::new(x, y), PreonVector::new(width, height)))
+    }
+
+    /// The rounded corners of [`PreonComponent::get_clip_rect`], matching the background.
+    pub fn get_clip_radius(&self) -> PreonCorners {
+        let radius = self.get_corner_radius();
+
+        if !self.style.overflow_x.is_clipped() || !self.style.overflow_y.is_clipped() {
+            return PreonCorners::ZERO;
         }
 
-        for &child in self.children.iter() {
-            child.layout();
+        if !self.is_scroll_view() {
+            return radius;
         }
+
+        // The content rect is inset by the padding, so its corners are tighter
+        let padding = self.get_padding();
+        PreonCorners::new(
+            (radius.top_left - padding.top.max(padding.left) as f32).max(0.0),
+            (radius.top_right - padding.top.max(padding.right) as f32).max(0.0),
+            (radius.bottom_right - padding.bottom.max(padding.right) as f32).max(0.0),
+            (radius.bottom_left - padding.bottom.max(padding.left) as f32).max(0.0),
+        )
     }
 
-    pub(crate) fn render(&mut self, pass: &mut PreonRenderPass) {
+    /// Renders the shapes drawn before the children of this component.
+    pub(crate) fn render(&self, pass: &mut Vec<PreonShape>) {
         let stages = [
             PreonComponentRenderStage::Background {
                 position: self.get_inner_position(),
@@ -237,29 +461,50 @@ impl PreonComponent {
         for stage in stages {
             match stage {
                 PreonComponentRenderStage::Background { position, size } => {
-                    match self.style.background {
-                        PreonBackground::Color(color) => pass.push(PreonShape::Rect {
+                    let color = match (self.get_background(), self.animated(PreonAnimatable::BackgroundColor)) {
+                        (PreonBackground::Image(image), _) => {
+                            pass.push(PreonShape::Rect {
+                                position,
+                                size,
+                                color: PreonColor::TRANSPARENT_BLACK,
+                                index: Some(image.index()),
+                                radius: self.get_corner_radius(),
+                            });
+
+                            None
+                        }
+                        // Also used while a missing background fades in or out
+                        (_, Some(PreonAnimationValue::Color(color))) => Some(color),
+                        (PreonBackground::Color(color), _) => Some(*color),
+                        _ => None,
+                    };
+
+                    if let Some(color) = color {
+                        pass.push(PreonShape::Rect {
                             position,
                             size,
                             color,
                             index: None,
-                            radius: self.style.corner_radius,
-                        }),
-                        PreonBackground::Image(ref image) => pass.push(PreonShape::Rect {
-                            position,
-                            size,
-                            color: PreonColor::TRANSPARENT_BLACK,
-                            index: Some(image.index()),
-                            radius: self.style.corner_radius,
-                        }),
-                        _ => (),
+                            radius: self.get_corner_radius(),
+                        });
                     }
                 }
                 PreonComponentRenderStage::Foreground { position, size } => {
-                    if !self.text.is_empty() {
+                    // The text is clipped along with the children
+                    if let Some(clip) = self.get_clip_rect() {
+                        pass.push(PreonShape::PushClip {
+                            position: clip.position,
+                            size: clip.size,
+                            radius: self.get_clip_radius(),
+                        });
+                    }
+
+                    if self.is_selectable() {
+                        self.render_text_input(pass);
+                    } else if !self.text.is_empty() {
                         pass.push(PreonShape::Text {
                             text_style: self.style.text_style.clone(),
-                            color: self.style.foreground_color,
+                            color: self.get_foreground_color(),
                             position,
                             size,
                             text: self.text.clone(),
@@ -295,10 +540,15 @@ impl PreonComponent {
                 color: PreonColor::from_hex("#6891c055"),
             });
         }
+    }
 
-        for child in self.children.iter_mut() {
-            Self::render(child, pass)
+    /// Renders the shapes drawn on top of the children of this component.
+    pub(crate) fn render_after_children(&self, pass: &mut Vec<PreonShape>) {
+        if self.get_clip_rect().is_some() {
+            pass.push(PreonShape::PopClip);
         }
+
+        self.render_scrollbars(pass);
     }
 }
 
@@ -312,13 +562,24 @@ impl Default for PreonComponent {
                 foreground_color: PreonColor::BLACK,
                 ..Default::default()
             },
+            parent: None,
             children: Vec::new(),
+            id: None,
+            classes: Vec::new(),
             text: String::new(),
             inner_size: PreonVector::zero(),
             inner_position: PreonVector::zero(),
-            id: None,
-            id_lookup_cache: Vec::new(),
             mouse_events: false,
+            focusable: false,
+            tab_index: None,
+            dirty: dirty::LAYOUT,
+            hovered: false,
+            focused: false,
+            scroll: None,
+            overflowing_x: false,
+            overflowing_y: false,
+            animation: None,
+            text_input: None,
         }
     }
 }
@@ -335,8 +596,17 @@ pub enum PreonComponentRenderStage {
     },
 }
 
+/// A finished component in a [`PreonComponentBuilder`], waiting to be pushed into an engine.
+#[derive(Debug, Clone)]
+struct PreonComponentBuilderNode {
+    component: PreonComponent,
+    children: Vec<PreonComponentBuilderNode>,
+}
+
 pub struct PreonComponentBuilder {
     pub stack: Vec<PreonComponent>,
+    /// Finished children of every component on `stack`, by stack index
+    children: Vec<Vec<PreonComponentBuilderNode>>,
 }
 
 #[allow(clippy::new_without_default)]
@@ -351,17 +621,22 @@ impl PreonComponentBuilder {
                 },
                 ..Default::default()
             }],
+            children: Vec::new(),
         }
     }
 
     pub fn from_component(component: PreonComponent) -> PreonComponentBuilder {
         Self {
             stack: vec![component],
+            children: Vec::new(),
         }
     }
 
     pub fn with_child(&mut self, child: PreonComponent) -> &mut PreonComponentBuilder {
-        self.current_mut().children.push(child);
+        self.push_node(PreonComponentBuilderNode {
+            component: child,
+            children: Vec::new(),
+        });
         self
     }
 
@@ -374,6 +649,20 @@ impl PreonComponentBuilder {
         self
     }
 
+    pub fn focusable(&mut self, focusable: bool) -> &mut PreonComponentBuilder {
+        self.stack.last_mut().unwrap().focusable = focusable;
+        self
+    }
+
+    /// Makes the component focusable, and puts it in front of components without a tab index
+    /// when pressing Tab.
+    pub fn tab_index(&mut self, tab_index: u32) -> &mut PreonComponentBuilder {
+        let component = self.stack.last_mut().unwrap();
+        component.focusable = true;
+        component.tab_index = Some(tab_index);
+        self
+    }
+
     pub fn id(&mut self, id: &str) -> &mut PreonComponentBuilder {
         self.stack.last_mut().unwrap().id = Some(id.to_string());
         self
@@ -384,14 +673,96 @@ impl PreonComponentBuilder {
         self
     }
 
+    pub fn class(&mut self, class: &str) -> &mut PreonComponentBuilder {
+        self.stack.last_mut().unwrap().classes.push(class.to_string());
+        self
+    }
+
+    pub fn class_string(&mut self, class: String) -> &mut PreonComponentBuilder {
+        self.stack.last_mut().unwrap().classes.push(class);
+        self
+    }
+
     pub fn end(&mut self) -> &mut PreonComponentBuilder {
-        let child = self.stack.pop().unwrap();
-        self.with_child(child);
+        let node = self.pop_node();
+        self.push_node(node);
         self
     }
 
+    /// Returns the root component without its children, use [`PreonComponentBuilder::build_into`]
+    /// to build the complete tree.
     pub fn build(&mut self) -> PreonComponent {
-        self.stack.pop().unwrap()
+        self.pop_node().component
+    }
+
+    /// Pushes the root component and all of its descendants into `engine` as a child of `parent`.
+    /// Components that haven't been `end()`ed yet are closed automatically.
+    ///
+    /// Returns the handle of the root component, and the handles of every component given an id
+    /// with [`PreonComponentBuilder::id`]. Like [`PreonEngine::find_by_id`], the first component
+    /// keeps a duplicate id.
+    ///
+    /// ```ignore
+    /// let root = engine.root;
+    /// let (list, ids) = PreonComponentBuilder::new()
+    ///     .start_label_str("Hello").id("greeting").end()
+    ///     .build_into(&mut engine, root);
+    /// ```
+    pub fn build_into(
+        &mut self,
+        engine: &mut PreonEngine,
+        parent: PreonComponentHandle,
+    ) -> (PreonComponentHandle, HashMap<String, PreonComponentHandle>) {
+        while self.stack.len() > 1 {
+            self.end();
+        }
+
+        let root = self.pop_node();
+        let mut ids = HashMap::new();
+        let mut stack = vec![(root, parent)];
+        let mut root_handle = None;
+
+        while let Some((node, parent)) = stack.pop() {
+            let id = node.component.id.clone();
+            let handle = engine.push_component(parent, node.component);
+            root_handle.get_or_insert(handle);
+
+            if let Some(id) = id {
+                ids.entry(id).or_insert(handle);
+            }
+
+            stack.extend(node.children.into_iter().rev().map(|child| (child, handle)));
+        }
+
+        (root_handle.unwrap(), ids)
+    }
+
+    fn pop_node(&mut self) -> PreonComponentBuilderNode {
+        let component = self.stack.pop().expect("PreonComponentBuilder is empty");
+        let index = self.stack.len();
+
+        let children = if self.children.len() > index {
+            let children = core::mem::take(&mut self.children[index]);
+            self.children.truncate(index);
+            children
+        } else {
+            Vec::new()
+        };
+
+        PreonComponentBuilderNode {
+            component,
+            children,
+        }
+    }
+
+    fn push_node(&mut self, node: PreonComponentBuilderNode) {
+        let index = self.stack.len() - 1;
+
+        if self.children.len() <= index {
+            self.children.resize_with(index + 1, Vec::new);
+        }
+
+        self.children[index].push(node);
     }
 
     pub fn current(&self) -> &PreonComponent {
@@ -402,3 +773,28 @@ impl PreonComponentBuilder {
         self.stack.last_mut().unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{panel::PreonComponentBuilderPanelExtension, PreonComponentBuilder};
+    use crate::PreonEngine;
+
+    #[test]
+    fn build_into_keeps_the_first_duplicate_id() {
+        let mut engine = PreonEngine::new();
+        let root = engine.root;
+
+        #[rustfmt::skip]
+        let (list, ids) = PreonComponentBuilder::new()
+            .start_panel_hex("#ffffff").id("row")
+                .start_panel_hex("#ffffff").id("row").end()
+            .end()
+            .start_panel_hex("#ffffff").id("row").end()
+            .build_into(&mut engine, root);
+
+        let first = engine.get_component(list).unwrap().children[0];
+        assert_eq!(ids.len(), 1);
+        assert_eq!(ids["row"], first);
+        assert_eq!(engine.find_by_id("row"), Some(first));
+    }
+}
@@ -0,0 +1,273 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{
+    layout::PreonLayout,
+    rendering::PreonShape,
+    style::{PreonOverflow, PreonStyle},
+    types::{PreonColor, PreonCorners, PreonRect, PreonVector},
+};
+
+use super::{PreonComponent, PreonComponentBuilder};
+
+/// Thickness of the scrollbars, drawn on top of the content along the right and bottom edges.
+pub const SCROLLBAR_SIZE: i32 = 8;
+
+/// Scrollbar thumbs never get shorter than this.
+const MIN_THUMB_SIZE: i32 = 24;
+
+/// Scroll position and settings of a scroll view, see [`PreonComponentBuilderScrollViewExtension`].
+#[derive(Debug, Clone)]
+pub struct PreonScrollState {
+    /// Keep scrolling for a moment after using the mouse wheel, slowing down over time.
+    pub inertia: bool,
+    pub scrollbar_color: PreonColor,
+    /// Managed by the engine, see [`PreonComponent::scroll_offset`].
+    pub(crate) offset: PreonVector<f32>,
+    /// The offset the children are currently moved by, `offset` rounded to whole pixels.
+    pub(crate) applied_offset: PreonVector<i32>,
+    /// Measured during layout, see [`PreonComponent::scroll_size`].
+    pub(crate) content_size: PreonVector<i32>,
+    /// In pixels per second, only used with inertia.
+    pub(crate) velocity: PreonVector<f32>,
+}
+
+impl Default for PreonScrollState {
+    fn default() -> Self {
+        Self {
+            inertia: false,
+            scrollbar_color: PreonColor::from_rgba8(0x00, 0x00, 0x00, 0x60),
+            offset: PreonVector::zero(),
+            applied_offset: PreonVector::zero(),
+            content_size: PreonVector::zero(),
+            velocity: PreonVector::zero(),
+        }
+    }
+}
+
+/// Which scrollbar of a scroll view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreonScrollAxis {
+    Horizontal,
+    Vertical,
+}
+
+impl PreonScrollAxis {
+    /// The part of `vector` along this axis.
+    #[inline]
+    pub fn along(self, vector: PreonVector<i32>) -> i32 {
+        match self {
+            PreonScrollAxis::Horizontal => vector.x,
+            PreonScrollAxis::Vertical => vector.y,
+        }
+    }
+}
+
+impl PreonComponent {
+    /// Returns true if this component was created with
+    /// [`start_scroll_view`](PreonComponentBuilderScrollViewExtension::start_scroll_view), or
+    /// scrolls along an axis with [`PreonOverflow::Scroll`].
+    #[inline(always)]
+    pub fn is_scroll_view(&self) -> bool {
+        self.scroll.is_some()
+            && (self.style.overflow_x == PreonOverflow::Scroll || self.style.overflow_y == PreonOverflow::Scroll)
+    }
+
+    /// The scroll state, `None` unless [`PreonComponent::is_scroll_view`].
+    fn scroll_state(&self) -> Option<&PreonScrollState> {
+        self.scroll.as_deref().filter(|_| self.is_scroll_view())
+    }
+
+    /// How far the content of a scroll view is scrolled, (0, 0) shows its top left corner. Always
+    /// zero for other components. Use [`PreonEngine::scroll_to`](crate::PreonEngine::scroll_to) to change it.
+    pub fn scroll_offset(&self) -> PreonVector<i32> {
+        self.scroll_state()
+            .map(|scroll| scroll.applied_offset)
+            .unwrap_or_else(PreonVector::zero)
+    }
+
+    /// The size of everything inside of a scroll view, measured from the top left corner of its
+    /// content rect. Zero for other components.
+    pub fn scroll_size(&self) -> PreonVector<i32> {
+        self.scroll_state()
+            .map(|scroll| scroll.content_size)
+            .unwrap_or_else(PreonVector::zero)
+    }
+
+    /// The largest possible [`PreonComponent::scroll_offset`], zero along axes without
+    /// [`PreonOverflow::Scroll`].
+    pub fn max_scroll_offset(&self) -> PreonVector<i32> {
+        let size = self.scroll_size() - self.get_content_size();
+
+        PreonVector::new(
+            if self.style.overflow_x == PreonOverflow::Scroll { size.x.max(0) } else { 0 },
+            if self.style.overflow_y == PreonOverflow::Scroll { size.y.max(0) } else { 0 },
+        )
+    }
+
+    /// Limits `offset` to the scrollable area.
+    pub(crate) fn clamp_scroll_offset(&self, offset: PreonVector<f32>) -> PreonVector<f32> {
+        let max = self.max_scroll_offset();
+
+        PreonVector::new(
+            offset.x.max(0.0).min(max.x as f32),
+            offset.y.max(0.0).min(max.y as f32),
+        )
+    }
+
+    /// The area the scrollbar of `axis` can move in, `None` if the content fits in that direction.
+    pub fn scrollbar_track(&self, axis: PreonScrollAxis) -> Option<PreonRect<i32>> {
+        let max = self.max_scroll_offset();
+        let inner = self.get_inner_rect();
+
+        // Leave the corner free if both scrollbars are visible
+        match axis {
+            PreonScrollAxis::Horizontal if max.x > 0 => Some(PreonRect::new(
+                PreonVector::new(inner.position.x, inner.position.y + inner.size.y - SCROLLBAR_SIZE),
+                PreonVector::new(
+                    inner.size.x - if max.y > 0 { SCROLLBAR_SIZE } else { 0 },
+                    SCROLLBAR_SIZE,
+                ),
+            )),
+            PreonScrollAxis::Vertical if max.y > 0 => Some(PreonRect::new(
+                PreonVector::new(inner.position.x + inner.size.x - SCROLLBAR_SIZE, inner.position.y),
+                PreonVector::new(
+                    SCROLLBAR_SIZE,
+                    inner.size.y - if max.x > 0 { SCROLLBAR_SIZE } else { 0 },
+                ),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The draggable part of the scrollbar of `axis`, its length shows how much of the content is
+    /// visible.
+    pub fn scrollbar_thumb(&self, axis: PreonScrollAxis) -> Option<PreonRect<i32>> {
+        let track = self.scrollbar_track(axis)?;
+        let (length, offset) = self.thumb_length_and_offset(axis, track);
+
+        Some(match axis {
+            PreonScrollAxis::Horizontal => PreonRect::new(
+                track.position + PreonVector::new(offset, 0),
+                PreonVector::new(length, track.size.y),
+            ),
+            PreonScrollAxis::Vertical => PreonRect::new(
+                track.position + PreonVector::new(0, offset),
+                PreonVector::new(track.size.x, length),
+            ),
+        })
+    }
+
+    /// Converts a thumb position along `axis` (relative to the start of the track) to a scroll offset.
+    pub(crate) fn thumb_to_scroll_offset(&self, axis: PreonScrollAxis, thumb_offset: i32) -> f32 {
+        let track = match self.scrollbar_track(axis) {
+            Some(track) => track,
+            None => return 0.0,
+        };
+
+        let (length, _) = self.thumb_length_and_offset(axis, track);
+        let track_length = axis.along(track.size);
+        let max = axis.along(self.max_scroll_offset());
+
+        if track_length <= length {
+            return 0.0;
+        }
+
+        thumb_offset as f32 / (track_length - length) as f32 * max as f32
+    }
+
+    fn thumb_length_and_offset(&self, axis: PreonScrollAxis, track: PreonRect<i32>) -> (i32, i32) {
+        let track_length = axis.along(track.size);
+        let visible = axis.along(self.get_content_size());
+        let total = axis.along(self.scroll_size());
+        let offset = axis.along(self.scroll_offset());
+        let max = axis.along(self.max_scroll_offset());
+
+        let length = (track_length as i64 * visible as i64 / total.max(1) as i64) as i32;
+        let length = length.max(MIN_THUMB_SIZE).min(track_length);
+        let offset = if max > 0 {
+            ((track_length - length) as i64 * offset as i64 / max as i64) as i32
+        } else {
+            0
+        };
+
+        (length, offset)
+    }
+
+    /// Draws the scrollbars of scroll views on top of their content.
+    pub(crate) fn render_scrollbars(&self, pass: &mut Vec<PreonShape>) {
+        let color = match self.scroll_state() {
+            Some(scroll) => scroll.scrollbar_color,
+            None => return,
+        };
+
+        for axis in [PreonScrollAxis::Horizontal, PreonScrollAxis::Vertical] {
+            if let Some(thumb) = self.scrollbar_thumb(axis) {
+                // Inset the thumb a little, so it doesn't touch the edge of the scroll view
+                pass.push(PreonShape::Rect {
+                    position: thumb.position + PreonVector::new(2, 2),
+                    size: thumb.size - PreonVector::new(4, 4),
+                    color,
+                    index: None,
+                    radius: PreonCorners::from_single((SCROLLBAR_SIZE - 4) as f32 / 2.0),
+                });
+            }
+        }
+    }
+}
+
+pub trait PreonComponentBuilderScrollViewExtension {
+    /// A component whose children can be larger than itself. Everything outside of its content
+    /// rect is clipped, and can be scrolled into view with the mouse wheel, the scrollbars and
+    /// PageUp/PageDown/Home/End while it (or one of its descendants) has focus.
+    ///
+    /// Scroll views don't resize to fit their children, give them a size with
+    /// [`min_size`](crate::style::PreonComponentBuilderStyleExtension::min_size) or
+    /// [`expand`](crate::style::PreonComponentBuilderStyleExtension::expand). Children are laid
+    /// out as rows. Use [`overflow_x`](crate::style::PreonComponentBuilderStyleExtension::overflow_x)
+    /// to only scroll vertically, or the other way around.
+    fn start_scroll_view(&mut self) -> &mut PreonComponentBuilder;
+    fn empty_scroll_view(&mut self) -> &mut PreonComponentBuilder;
+    /// Keep scrolling for a moment after using the mouse wheel, slowing down over time.
+    fn inertial_scrolling(&mut self, inertia: bool) -> &mut PreonComponentBuilder;
+    fn scrollbar_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder;
+}
+
+impl PreonComponentBuilderScrollViewExtension for PreonComponentBuilder {
+    fn start_scroll_view(&mut self) -> &mut PreonComponentBuilder {
+        self.stack.push(PreonComponent {
+            style: PreonStyle {
+                layout: PreonLayout::Rows,
+                overflow_x: PreonOverflow::Scroll,
+                overflow_y: PreonOverflow::Scroll,
+                ..self.inherited_style()
+            },
+            focusable: true,
+            scroll: Some(Box::default()),
+            ..Default::default()
+        });
+
+        self
+    }
+
+    fn empty_scroll_view(&mut self) -> &mut PreonComponentBuilder {
+        self.start_scroll_view().end()
+    }
+
+    fn inertial_scrolling(&mut self, inertia: bool) -> &mut PreonComponentBuilder {
+        match self.current_mut().scroll {
+            Some(ref mut scroll) => scroll.inertia = inertia,
+            None => log::warn!("inertial_scrolling() only works on scroll views"),
+        }
+
+        self
+    }
+
+    fn scrollbar_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder {
+        match self.current_mut().scroll {
+            Some(ref mut scroll) => scroll.scrollbar_color = color,
+            None => log::warn!("scrollbar_color() only works on scroll views"),
+        }
+
+        self
+    }
+}
@@ -0,0 +1,74 @@
+use core::str::FromStr;
+
+use alloc::string::String;
+
+use crate::types::PreonVector;
+
+use super::{text_input::PreonComponentBuilderTextInputExtension, PreonComponent, PreonComponentBuilder};
+
+impl PreonComponent {
+    /// Returns true if this component was created with
+    /// [`start_text_area`](PreonComponentBuilderTextAreaExtension::start_text_area).
+    #[inline(always)]
+    pub fn is_text_area(&self) -> bool {
+        match self.text_input {
+            Some(ref state) => state.multiline && !state.read_only,
+            None => false,
+        }
+    }
+}
+
+pub trait PreonComponentBuilderTextAreaExtension {
+    /// Multiple lines of editable text, wrapped inside of the content width. It grows with its text
+    /// up to the [`max_height`](PreonComponentBuilderTextAreaExtension::max_height), and scrolls
+    /// vertically after that.
+    ///
+    /// Takes the same keys as a [text input](super::text_input::PreonComponentBuilderTextInputExtension::start_text_input),
+    /// with Home/End moving along the current line (along the text with Ctrl), Up/Down/PageUp/PageDown
+    /// keeping the column the caret started in, and Return inserting a line break.
+    /// [`PreonEvent::Submitted`](crate::events::PreonEvent::Submitted) is emitted with Ctrl+Return.
+    fn start_text_area(&mut self, text: String) -> &mut PreonComponentBuilder;
+    fn start_text_area_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder;
+    fn empty_text_area(&mut self, text: String) -> &mut PreonComponentBuilder;
+    fn empty_text_area_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder;
+    /// Height (padding included) a text area stops growing at.
+    fn max_height(&mut self, max_height: i32) -> &mut PreonComponentBuilder;
+}
+
+impl PreonComponentBuilderTextAreaExtension for PreonComponentBuilder {
+    fn start_text_area(&mut self, text: String) -> &mut PreonComponentBuilder {
+        self.start_text_input(text);
+
+        let component = self.current_mut();
+        component.text_input.as_mut().unwrap().multiline = true;
+
+        // Room for three lines before it starts growing
+        let line_height = libm::ceilf(component.style.text_style.size * 1.2) as i32;
+        component.style.min_size = PreonVector::new(240, line_height * 3 + component.style.padding.y());
+
+        self
+    }
+
+    fn start_text_area_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder {
+        self.start_text_area(String::from_str(text).unwrap())
+    }
+
+    fn empty_text_area(&mut self, text: String) -> &mut PreonComponentBuilder {
+        self.start_text_area(text).end()
+    }
+
+    fn empty_text_area_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder {
+        self.start_text_area_str(text).end()
+    }
+
+    fn max_height(&mut self, max_height: i32) -> &mut PreonComponentBuilder {
+        match self.current_mut().text_input {
+            Some(ref mut state) if state.multiline && !state.read_only => {
+                state.max_height = Some(max_height)
+            }
+            _ => log::warn!("max_height() only works on text areas"),
+        }
+
+        self
+    }
+}
@@ -0,0 +1,1509 @@
+use core::{ops::Range, str::FromStr};
+
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+
+use crate::{
+    clipboard::PreonClipboard,
+    layout::PreonLayout,
+    rendering::{PreonShape, PreonTextMeasurer},
+    style::{PreonBackground, PreonOverflow, PreonStateStyle, PreonStyle, PreonTextStyle},
+    types::{PreonBorder, PreonColor, PreonCorners, PreonRect, PreonVector},
+};
+
+use super::{PreonComponent, PreonComponentBuilder};
+
+/// Width of the caret of text inputs.
+pub const CARET_WIDTH: i32 = 2;
+
+/// Thickness of the line under text that's being composed with an input method.
+const PREEDIT_UNDERLINE: i32 = 1;
+
+/// Shown instead of every character of a password.
+const PASSWORD_CHAR: char = '\u{2022}';
+
+/// Number of edits that can be undone.
+const MAX_UNDO: usize = 100;
+
+/// Ways to move the caret of a text input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PreonCaretMotion {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    /// The start of the line the caret is on.
+    LineStart,
+    /// The end of the line the caret is on.
+    LineEnd,
+    /// The start of the text.
+    Home,
+    /// The end of the text.
+    End,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+}
+
+/// A line of a text input, after wrapping.
+#[derive(Debug, Clone)]
+pub(crate) struct PreonTextLine {
+    /// The bytes of the text on this line, without the line break.
+    pub(crate) range: Range<usize>,
+    /// The caret positions on this line. A wrapped line leaves the position at its end to the next line.
+    pub(crate) carets: Range<usize>,
+    /// Distance from the start of the line to its end, trailing whitespace included.
+    pub(crate) width: f32,
+}
+
+/// An edit of a text input, undone by putting `removed` back in place of `inserted`.
+#[derive(Debug, Clone)]
+pub(crate) struct PreonTextEdit {
+    start: usize,
+    removed: String,
+    inserted: String,
+    /// The selection (anchor, caret) before the edit.
+    selection: (usize, usize),
+}
+
+impl PreonTextEdit {
+    /// Adds `edit` to this one if it continues typing or deleting in the same place, returns false
+    /// if it doesn't.
+    fn merge(&mut self, edit: &PreonTextEdit) -> bool {
+        let typing = self.removed.is_empty() && edit.removed.is_empty();
+        let deleting = self.inserted.is_empty() && edit.inserted.is_empty();
+
+        if typing && edit.start == self.start + self.inserted.len() {
+            // Every word is undone separately
+            let new_word = self.inserted.ends_with(char::is_whitespace)
+                && !edit.inserted.starts_with(char::is_whitespace);
+
+            if !new_word {
+                self.inserted.push_str(&edit.inserted);
+            }
+
+            !new_word
+        } else if deleting && edit.start + edit.removed.len() == self.start {
+            // Backspace
+            self.removed.insert_str(0, &edit.removed);
+            self.start = edit.start;
+            true
+        } else if deleting && edit.start == self.start {
+            // Delete
+            self.removed.push_str(&edit.removed);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if `text` still has the inserted text where the edit left it. It doesn't after
+    /// the text was changed without going through the text input.
+    fn is_done(&self, text: &str) -> bool {
+        text.get(self.start..self.start + self.inserted.len()) == Some(self.inserted.as_str())
+    }
+
+    /// Same as [`PreonTextEdit::is_done`], for an edit that has been undone.
+    fn is_undone(&self, text: &str) -> bool {
+        text.get(self.start..self.start + self.removed.len()) == Some(self.removed.as_str())
+    }
+}
+
+/// Caret, selection and settings of a text input, see [`PreonComponentBuilderTextInputExtension`].
+/// Text areas share it, see
+/// [`PreonComponentBuilderTextAreaExtension`](super::text_area::PreonComponentBuilderTextAreaExtension).
+#[derive(Debug, Clone)]
+pub struct PreonTextInputState {
+    /// Shown while the text is empty.
+    pub placeholder: String,
+    pub placeholder_color: PreonColor,
+    pub selection_color: PreonColor,
+    /// Shows a bullet for every character, word-wise movement skips the whole text.
+    pub password: bool,
+    /// Maximum number of characters, anything typed after that is dropped.
+    pub max_length: Option<usize>,
+    /// Text areas grow with their text up to this height (padding included), and scroll after that.
+    pub max_height: Option<i32>,
+    /// Set for text areas, which wrap their text and take line breaks.
+    pub(crate) multiline: bool,
+    /// Set for selectable labels, whose text can be selected and copied but not edited.
+    pub(crate) read_only: bool,
+    /// Set if making a label selectable turned on its mouse events, so they're turned off again
+    /// together with the selection.
+    pub(crate) enabled_mouse_events: bool,
+    /// Byte index of the caret in the text.
+    pub(crate) caret: usize,
+    /// Byte index of the other end of the selection, same as `caret` without a selection.
+    pub(crate) anchor: usize,
+    /// Distance from the start of the line Up and Down try to keep, set by the first of them in a row.
+    pub(crate) preferred_x: Option<f32>,
+    /// Text being composed with an input method, shown at the caret. Empty while not composing.
+    pub(crate) preedit: String,
+    /// The cursor inside of `preedit`, see [`PreonUserEvent::ImePreedit`](crate::events::PreonUserEvent::ImePreedit).
+    pub(crate) preedit_cursor: Option<Range<usize>>,
+    /// Every caret position (byte index, distance from the start of its line), measured before painting.
+    pub(crate) carets: Vec<(usize, f32)>,
+    pub(crate) lines: Vec<PreonTextLine>,
+    pub(crate) line_height: f32,
+    /// Height of the content rect the text was last measured for.
+    pub(crate) view_height: f32,
+    /// How far the text is moved to keep the caret visible.
+    pub(crate) scroll: PreonVector<f32>,
+    /// Set when the caret moved, so the next measurement scrolls it into view.
+    pub(crate) reveal_caret: bool,
+    pub(crate) undo: Vec<PreonTextEdit>,
+    pub(crate) redo: Vec<PreonTextEdit>,
+}
+
+impl Default for PreonTextInputState {
+    fn default() -> Self {
+        Self {
+            placeholder: String::new(),
+            placeholder_color: PreonColor::from_rgba8(0x00, 0x00, 0x00, 0x80),
+            selection_color: PreonColor::from_rgba8(0x37, 0x63, 0xF2, 0x60),
+            password: false,
+            max_length: None,
+            max_height: None,
+            multiline: false,
+            read_only: false,
+            enabled_mouse_events: false,
+            caret: 0,
+            anchor: 0,
+            preferred_x: None,
+            preedit: String::new(),
+            preedit_cursor: None,
+            carets: Vec::new(),
+            lines: Vec::new(),
+            line_height: 0.0,
+            view_height: 0.0,
+            scroll: PreonVector::zero(),
+            reveal_caret: true,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl PreonTextInputState {
+    /// Byte index of the caret in the text.
+    #[inline]
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// The selected bytes of the text, empty without a selection.
+    pub fn selection(&self) -> Range<usize> {
+        self.caret.min(self.anchor)..self.caret.max(self.anchor)
+    }
+
+    #[inline]
+    pub fn has_selection(&self) -> bool {
+        self.caret != self.anchor
+    }
+
+    /// Returns true while text is being composed with an input method. Lines and carets are measured
+    /// for the text with the composition in it then, see [`PreonTextInputState::compose`].
+    #[inline]
+    pub fn is_composing(&self) -> bool {
+        !self.preedit.is_empty()
+    }
+
+    /// Shows `preedit` at the caret, an empty one ends the composition.
+    pub(crate) fn set_preedit(&mut self, preedit: String, cursor: Option<Range<usize>>) {
+        // Input methods might point inside of a character, or past the end
+        self.preedit_cursor =
+            cursor.map(|cursor| clamp_index(&preedit, cursor.start)..clamp_index(&preedit, cursor.end));
+        self.preedit = preedit;
+        self.reveal_caret = true;
+    }
+
+    /// `text` with the composition at the caret, as it's measured and drawn.
+    pub(crate) fn compose<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.is_composing() {
+            return Cow::Borrowed(text);
+        }
+
+        let mut composed = String::with_capacity(text.len() + self.preedit.len());
+        composed.push_str(&text[..self.caret]);
+        composed.push_str(&self.preedit);
+        composed.push_str(&text[self.caret..]);
+
+        Cow::Owned(composed)
+    }
+
+    /// Byte index in the composed text where the caret is drawn, `None` if it's hidden by the input method.
+    pub(crate) fn display_caret(&self) -> Option<usize> {
+        if !self.is_composing() {
+            return Some(self.caret);
+        }
+
+        self.preedit_cursor
+            .as_ref()
+            .map(|cursor| self.caret + cursor.start)
+    }
+
+    /// Moves the caret to `caret`, keeping the other end of the selection where it is if `extend` is set.
+    pub(crate) fn set_caret(&mut self, caret: usize, extend: bool) {
+        self.caret = caret;
+        self.preferred_x = None;
+        self.reveal_caret = true;
+
+        if !extend {
+            self.anchor = caret;
+        }
+    }
+
+    /// Selects from `anchor` to `caret`, moved inside of `text` and onto character boundaries.
+    pub(crate) fn select(&mut self, text: &str, anchor: usize, caret: usize) {
+        let caret = clamp_index(text, caret);
+
+        if caret != self.caret {
+            self.preferred_x = None;
+            self.reveal_caret = true;
+        }
+
+        self.anchor = clamp_index(text, anchor);
+        self.caret = caret;
+    }
+
+    pub(crate) fn move_caret(&mut self, text: &str, motion: PreonCaretMotion, extend: bool) {
+        // Moving left or right without extending jumps to that side of the selection
+        if !extend && self.has_selection() {
+            match motion {
+                PreonCaretMotion::Left | PreonCaretMotion::WordLeft => {
+                    return self.set_caret(self.selection().start, false)
+                }
+                PreonCaretMotion::Right | PreonCaretMotion::WordRight => {
+                    return self.set_caret(self.selection().end, false)
+                }
+                _ => (),
+            }
+        }
+
+        let caret = match motion {
+            PreonCaretMotion::Left => previous_char_boundary(text, self.caret),
+            PreonCaretMotion::Right => next_char_boundary(text, self.caret),
+            // Word boundaries would give away the password
+            PreonCaretMotion::WordLeft if self.password => 0,
+            PreonCaretMotion::WordRight if self.password => text.len(),
+            PreonCaretMotion::WordLeft => previous_word_boundary(text, self.caret),
+            PreonCaretMotion::WordRight => next_word_boundary(text, self.caret),
+            PreonCaretMotion::LineStart => match self.lines.get(self.line_of(self.caret)) {
+                Some(line) => line.range.start,
+                None => 0,
+            },
+            PreonCaretMotion::LineEnd => match self.lines.get(self.line_of(self.caret)) {
+                Some(line) => self.carets[line.carets.end - 1].0,
+                None => text.len(),
+            },
+            PreonCaretMotion::Home => 0,
+            PreonCaretMotion::End => text.len(),
+            PreonCaretMotion::Up
+            | PreonCaretMotion::Down
+            | PreonCaretMotion::PageUp
+            | PreonCaretMotion::PageDown => {
+                // A page is every line that fits on screen
+                let page = if self.line_height > 0.0 {
+                    ((self.view_height / self.line_height) as isize).max(1)
+                } else {
+                    1
+                };
+                let lines = match motion {
+                    PreonCaretMotion::Up => -1,
+                    PreonCaretMotion::Down => 1,
+                    PreonCaretMotion::PageUp => -page,
+                    _ => page,
+                };
+
+                let x = match self.preferred_x {
+                    Some(x) => x,
+                    None => self.caret_position(self.caret).x,
+                };
+                let line = self.line_of(self.caret) as isize + lines;
+
+                // Moving past the first or last line goes to the start or end of the text
+                let caret = if line < 0 {
+                    0
+                } else if line as usize >= self.lines.len() {
+                    text.len()
+                } else {
+                    self.index_in_line(line as usize, x)
+                };
+
+                self.set_caret(clamp_index(text, caret), extend);
+                self.preferred_x = Some(x);
+                return;
+            }
+        };
+
+        // Lines are measured during the last paint, the text might have changed since
+        self.set_caret(clamp_index(text, caret), extend);
+    }
+
+    /// Replaces the selection with `insert`, cut off at the maximum length. Returns false if
+    /// nothing changed.
+    ///
+    /// With `merge` set, the edit is undone together with the previous one if it continues it
+    /// (e.g. typing the next character of a word).
+    pub(crate) fn insert(&mut self, text: &mut String, insert: &str, merge: bool) -> bool {
+        self.clamp_selection(text);
+        let selection = (self.anchor, self.caret);
+        self.replace(text, insert, merge, selection)
+    }
+
+    /// Replaces the selection with `insert`, remembering `before` as the selection to restore when
+    /// undoing it.
+    fn replace(&mut self, text: &mut String, insert: &str, merge: bool, before: (usize, usize)) -> bool {
+        if self.read_only {
+            return false;
+        }
+
+        let selection = self.selection();
+
+        let insert = match self.max_length {
+            Some(max_length) => {
+                let kept = text.chars().count() - text[selection.clone()].chars().count();
+
+                match insert.char_indices().nth(max_length.saturating_sub(kept)) {
+                    Some((end, _)) => &insert[..end],
+                    None => insert,
+                }
+            }
+            None => insert,
+        };
+
+        if insert.is_empty() && selection.is_empty() {
+            return false;
+        }
+
+        let edit = PreonTextEdit {
+            start: selection.start,
+            removed: String::from(&text[selection.clone()]),
+            inserted: String::from(insert),
+            selection: before,
+        };
+
+        text.replace_range(selection.clone(), insert);
+        self.set_caret(selection.start + insert.len(), false);
+
+        // Anything undone can't be redone after a new edit
+        self.redo.clear();
+
+        let merged = match self.undo.last_mut() {
+            Some(last) if merge => last.merge(&edit),
+            _ => false,
+        };
+
+        if !merged {
+            if self.undo.len() == MAX_UNDO {
+                self.undo.remove(0);
+            }
+
+            self.undo.push(edit);
+        }
+
+        true
+    }
+
+    /// Deletes the selection, or everything between the caret and where `motion` would move it.
+    /// Returns false if nothing changed.
+    pub(crate) fn delete(&mut self, text: &mut String, motion: PreonCaretMotion) -> bool {
+        if self.read_only {
+            return false;
+        }
+
+        self.clamp_selection(text);
+        let before = (self.anchor, self.caret);
+        let merge = !self.has_selection();
+
+        if merge {
+            self.move_caret(text, motion, true);
+        }
+
+        self.replace(text, "", merge, before)
+    }
+
+    /// Puts the selection on `clipboard`, and removes it from the text if `cut` is set. Returns false
+    /// if the text didn't change. Passwords can't be copied.
+    pub(crate) fn copy(&mut self, text: &mut String, clipboard: &mut dyn PreonClipboard, cut: bool) -> bool {
+        self.clamp_selection(text);
+
+        if !self.has_selection() || self.password {
+            return false;
+        }
+
+        clipboard.set_text(String::from(&text[self.selection()]));
+        cut && self.insert(text, "", false)
+    }
+
+    /// Replaces the selection with the text on `clipboard`. Line breaks become spaces in single
+    /// line text inputs. Returns false if the text didn't change.
+    pub(crate) fn paste(&mut self, text: &mut String, clipboard: &mut dyn PreonClipboard) -> bool {
+        let pasted = match clipboard.get_text() {
+            Some(pasted) => pasted.replace("\r\n", "\n"),
+            None => return false,
+        };
+
+        let pasted = if self.multiline {
+            pasted
+        } else {
+            pasted.replace(['\r', '\n'], " ")
+        };
+
+        self.insert(text, &pasted, false)
+    }
+
+    /// Reverts the last edit, returns false if there's nothing to undo.
+    pub(crate) fn undo(&mut self, text: &mut String) -> bool {
+        let edit = match self.undo.pop() {
+            Some(edit) => edit,
+            None => return false,
+        };
+
+        if !edit.is_done(text) {
+            self.clear_history();
+            return false;
+        }
+
+        text.replace_range(edit.start..edit.start + edit.inserted.len(), &edit.removed);
+        self.select(text, edit.selection.0, edit.selection.1);
+        self.reveal_caret = true;
+        self.redo.push(edit);
+
+        true
+    }
+
+    /// Applies the last undone edit again, returns false if there's nothing to redo.
+    pub(crate) fn redo(&mut self, text: &mut String) -> bool {
+        let edit = match self.redo.pop() {
+            Some(edit) => edit,
+            None => return false,
+        };
+
+        if !edit.is_undone(text) {
+            self.clear_history();
+            return false;
+        }
+
+        text.replace_range(edit.start..edit.start + edit.removed.len(), &edit.inserted);
+        self.set_caret(edit.start + edit.inserted.len(), false);
+        self.undo.push(edit);
+
+        true
+    }
+
+    /// Forgets every edit, used when the text is replaced from outside of the text input.
+    pub(crate) fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Moves the selection back inside of `text`, which might have been changed without going
+    /// through the text input.
+    pub(crate) fn clamp_selection(&mut self, text: &str) {
+        self.select(text, self.anchor, self.caret);
+    }
+
+    /// The line the caret position at byte `index` is on.
+    pub(crate) fn line_of(&self, index: usize) -> usize {
+        self.lines
+            .partition_point(|line| line.range.start <= index)
+            .saturating_sub(1)
+    }
+
+    /// Position of the caret at byte `index`, relative to the start of the first line.
+    pub(crate) fn caret_position(&self, index: usize) -> PreonVector<f32> {
+        let line = self.line_of(index);
+
+        match self.lines.get(line) {
+            Some(line_info) => PreonVector::new(
+                self.x_in_line(line_info, index),
+                line as f32 * self.line_height,
+            ),
+            None => PreonVector::zero(),
+        }
+    }
+
+    /// Distance from the start of `line` to the caret position at byte `index` on it.
+    pub(crate) fn x_in_line(&self, line: &PreonTextLine, index: usize) -> f32 {
+        if index >= line.range.end {
+            return line.width;
+        }
+
+        let carets = &self.carets[line.carets.clone()];
+        let next = carets.partition_point(|&(caret, _)| caret < index);
+
+        match carets.get(next).or_else(|| carets.last()) {
+            Some(&(_, x)) => x,
+            None => 0.0,
+        }
+    }
+
+    /// The caret position closest to `position`, relative to the start of the first line.
+    pub(crate) fn index_at(&self, position: PreonVector<f32>) -> usize {
+        if self.lines.is_empty() {
+            return 0;
+        }
+
+        // Above or below the text counts as the first or last line
+        let line = if self.line_height > 0.0 {
+            ((position.y / self.line_height).max(0.0) as usize).min(self.lines.len() - 1)
+        } else {
+            0
+        };
+
+        self.index_in_line(line, position.x)
+    }
+
+    /// The caret position on `line` closest to `x`.
+    fn index_in_line(&self, line: usize, x: f32) -> usize {
+        let carets = &self.carets[self.lines[line].carets.clone()];
+        let next = carets.partition_point(|&(_, caret)| caret < x);
+
+        match (next.checked_sub(1).map(|before| carets[before]), carets.get(next)) {
+            (Some((before, before_x)), Some(&(after, after_x))) => {
+                if x - before_x < after_x - x {
+                    before
+                } else {
+                    after
+                }
+            }
+            (Some((before, _)), None) => before,
+            (None, Some(&(after, _))) => after,
+            (None, None) => 0,
+        }
+    }
+
+    /// The text as it's shown on screen.
+    pub(crate) fn display_text(&self, text: &str) -> String {
+        if self.password {
+            text.chars().map(|_| PASSWORD_CHAR).collect()
+        } else {
+            String::from(text)
+        }
+    }
+
+    /// Height of every line of the text together.
+    #[inline]
+    pub(crate) fn text_height(&self) -> f32 {
+        self.lines.len() as f32 * self.line_height
+    }
+
+    /// The lines that can be seen through a content rect of `height`. Selectable labels show all of
+    /// them, like other labels.
+    pub(crate) fn visible_lines(&self, height: i32) -> Range<usize> {
+        if !self.multiline || self.read_only || self.line_height <= 0.0 {
+            return 0..self.lines.len();
+        }
+
+        let first = (self.scroll.y / self.line_height) as usize;
+        let last = libm::ceilf((self.scroll.y + height as f32) / self.line_height) as usize;
+
+        first.min(self.lines.len())..last.min(self.lines.len())
+    }
+
+    /// Measures every caret position, wrapping text areas inside of the width of `size`, and
+    /// scrolls the caret into view after it moved. Returns true if the height of the text changed.
+    pub(crate) fn measure(
+        &mut self,
+        text: &str,
+        style: &PreonTextStyle,
+        measurer: &dyn PreonTextMeasurer,
+        size: PreonVector<i32>,
+    ) -> bool {
+        let old_height = self.text_height();
+
+        // The text might have been changed without going through the text input
+        self.clamp_selection(text);
+
+        // Compositions are measured as part of the text
+        let composed;
+        let text = if self.is_composing() {
+            composed = self.compose(text).into_owned();
+            composed.as_str()
+        } else {
+            text
+        };
+
+        self.carets.clear();
+        self.lines.clear();
+        self.line_height = measurer.line_height(style);
+        self.view_height = size.y as f32;
+
+        let width = (size.x - CARET_WIDTH) as f32;
+        let mut line_start = 0;
+        let mut line_caret = 0;
+        let mut x = 0.0;
+        let mut previous = None;
+        // Where the line can be wrapped (byte index, caret), after the last whitespace
+        let mut wrap_point = None;
+
+        for (index, ch) in text.char_indices() {
+            if ch == '\n' && self.multiline {
+                self.carets.push((index, x));
+                self.lines.push(PreonTextLine {
+                    range: line_start..index,
+                    carets: line_caret..self.carets.len(),
+                    width: x,
+                });
+
+                line_start = index + 1;
+                line_caret = self.carets.len();
+                x = 0.0;
+                previous = None;
+                wrap_point = None;
+                continue;
+            }
+
+            let shown = if self.password { PASSWORD_CHAR } else { ch };
+
+            if let Some(previous) = previous.replace(shown) {
+                x += measurer.kerning(previous, shown, style);
+            }
+
+            let advance = measurer.advance(shown, style);
+
+            // Whitespace may hang past the end of the line, words too long for a line are broken up
+            if self.multiline && x + advance > width && index > line_start && !ch.is_whitespace() {
+                let (wrap_index, wrap_caret) = wrap_point.unwrap_or((index, self.carets.len()));
+                let wrap_x = match self.carets.get(wrap_caret) {
+                    Some(&(_, wrap_x)) => wrap_x,
+                    None => x,
+                };
+
+                self.lines.push(PreonTextLine {
+                    range: line_start..wrap_index,
+                    carets: line_caret..wrap_caret,
+                    width: wrap_x,
+                });
+
+                // Anything after the wrap point moves to the start of the next line
+                for caret in self.carets[wrap_caret..].iter_mut() {
+                    caret.1 -= wrap_x;
+                }
+
+                x -= wrap_x;
+                line_start = wrap_index;
+                line_caret = wrap_caret;
+                wrap_point = None;
+            }
+
+            self.carets.push((index, x));
+            x += advance;
+
+            if ch.is_whitespace() {
+                wrap_point = Some((index + ch.len_utf8(), self.carets.len()));
+            }
+        }
+
+        self.carets.push((text.len(), x));
+        self.lines.push(PreonTextLine {
+            range: line_start..text.len(),
+            carets: line_caret..self.carets.len(),
+            width: x,
+        });
+
+        // Don't leave empty space behind the text, and show the caret after it moved
+        let caret = self.caret_position(self.display_caret().unwrap_or(self.caret));
+        let reveal = core::mem::take(&mut self.reveal_caret);
+
+        if self.read_only {
+            self.scroll = PreonVector::zero();
+        } else if self.multiline {
+            let height = size.y as f32;
+            let mut scroll = self.scroll.y;
+
+            if reveal {
+                scroll = scroll.min(caret.y).max(caret.y + self.line_height - height);
+            }
+
+            self.scroll = PreonVector::new(0.0, scroll.min((self.text_height() - height).max(0.0)).max(0.0));
+        } else {
+            let mut scroll = self.scroll.x.min((x - width).max(0.0));
+
+            if reveal {
+                scroll = scroll.min(caret.x).max(caret.x - width);
+            }
+
+            self.scroll = PreonVector::new(scroll.max(0.0), 0.0);
+        }
+
+        self.text_height() != old_height
+    }
+
+    /// Height a text area grows to for its text, `None` for single line text inputs and selectable
+    /// labels.
+    pub(crate) fn grow_height(&self, padding: PreonBorder) -> Option<i32> {
+        if !self.multiline || self.read_only {
+            return None;
+        }
+
+        let height = libm::ceilf(self.text_height()) as i32 + padding.y();
+
+        Some(match self.max_height {
+            Some(max_height) => height.min(max_height),
+            None => height,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+fn char_class(ch: char) -> CharClass {
+    if ch.is_whitespace() {
+        CharClass::Whitespace
+    } else if ch.is_alphanumeric() || ch == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// Moves `index` inside of `text`, onto the start of the character it's in.
+pub(crate) fn clamp_index(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+pub(crate) fn previous_char_boundary(text: &str, index: usize) -> usize {
+    text[..index].char_indices().next_back().map_or(0, |(index, _)| index)
+}
+
+pub(crate) fn next_char_boundary(text: &str, index: usize) -> usize {
+    text[index..].chars().next().map_or(index, |ch| index + ch.len_utf8())
+}
+
+/// The start of the word before `index`, skipping any whitespace in between.
+pub(crate) fn previous_word_boundary(text: &str, index: usize) -> usize {
+    let mut chars = text[..index]
+        .char_indices()
+        .rev()
+        .skip_while(|&(_, ch)| ch.is_whitespace());
+
+    let (mut boundary, class) = match chars.next() {
+        Some((index, ch)) => (index, char_class(ch)),
+        None => return 0,
+    };
+
+    for (index, ch) in chars {
+        if char_class(ch) != class {
+            break;
+        }
+
+        boundary = index;
+    }
+
+    boundary
+}
+
+/// The start of the word after `index`, the end of the text if there is none.
+pub(crate) fn next_word_boundary(text: &str, index: usize) -> usize {
+    let rest = &text[index..];
+    let mut chars = rest.char_indices().peekable();
+
+    if let Some(&(_, first)) = chars.peek() {
+        let class = char_class(first);
+
+        while matches!(chars.peek(), Some(&(_, ch)) if char_class(ch) == class) {
+            chars.next();
+        }
+    }
+
+    while matches!(chars.peek(), Some(&(_, ch)) if ch.is_whitespace()) {
+        chars.next();
+    }
+
+    index + chars.peek().map_or(rest.len(), |&(offset, _)| offset)
+}
+
+/// The word (or run of whitespace or punctuation) at `index`, selected by double clicking.
+pub(crate) fn word_at(text: &str, index: usize) -> Range<usize> {
+    let class = match text[index..].chars().next().or_else(|| text[..index].chars().next_back()) {
+        Some(ch) => char_class(ch),
+        None => return index..index,
+    };
+
+    let start = text[..index]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, ch)| char_class(ch) == class)
+        .last()
+        .map_or(index, |(start, _)| start);
+    let end = text[index..]
+        .char_indices()
+        .find(|&(_, ch)| char_class(ch) != class)
+        .map_or(text.len(), |(end, _)| index + end);
+
+    start..end
+}
+
+impl PreonComponent {
+    /// Returns true if this component was created with
+    /// [`start_text_input`](PreonComponentBuilderTextInputExtension::start_text_input), or is a
+    /// text area.
+    #[inline(always)]
+    pub fn is_text_input(&self) -> bool {
+        match self.text_input {
+            Some(ref state) => !state.read_only,
+            None => false,
+        }
+    }
+
+    /// Returns true if the text of this component can be selected, for text inputs, text areas and
+    /// [selectable](super::label::PreonComponentBuilderLabelExtension::selectable) labels.
+    #[inline(always)]
+    pub fn is_selectable(&self) -> bool {
+        self.text_input.is_some()
+    }
+
+    /// The caret and selection of a text input (or selectable label), `None` for other components.
+    /// Use [`PreonEngine::select_text`](crate::PreonEngine::select_text) to change the selection.
+    #[inline(always)]
+    pub fn text_input(&self) -> Option<&PreonTextInputState> {
+        self.text_input.as_deref()
+    }
+
+    /// The top left corner of the text of a text input, moved to keep the caret visible.
+    pub(crate) fn text_origin(&self) -> PreonVector<i32> {
+        let content = self.get_content_rect();
+
+        let state = match self.text_input {
+            Some(ref state) => state,
+            None => return content.position,
+        };
+
+        let scroll = PreonVector::new(
+            libm::roundf(state.scroll.x) as i32,
+            libm::roundf(state.scroll.y) as i32,
+        );
+
+        // Single lines are vertically centered, text areas start at the top
+        if state.multiline {
+            content.position - scroll
+        } else {
+            PreonVector::new(
+                content.position.x - scroll.x,
+                content.position.y + ((content.size.y - libm::ceilf(state.line_height) as i32) / 2).max(0),
+            )
+        }
+    }
+
+    /// Draws the selection, text (or placeholder) and caret of a text input.
+    pub(crate) fn render_text_input(&self, pass: &mut Vec<PreonShape>) {
+        let state = match self.text_input {
+            Some(ref state) => state,
+            None => return,
+        };
+
+        let origin = self.text_origin();
+        let content_size = self.get_content_size();
+        let line_height = libm::ceilf(state.line_height) as i32;
+        let visible_lines = state.visible_lines(content_size.y);
+        let line_position =
+            |line: usize| origin + PreonVector::new(0, libm::roundf(line as f32 * state.line_height) as i32);
+        let text = state.compose(&self.text);
+
+        if self.focused && state.has_selection() && !state.is_composing() {
+            let selection = state.selection();
+
+            for index in visible_lines.clone() {
+                let line = &state.lines[index];
+
+                // Wrapped lines continue right at their end, line breaks take up a byte
+                let wrapped =
+                    matches!(state.lines.get(index + 1), Some(next) if next.range.start == line.range.end);
+
+                if selection.end < line.range.start
+                    || selection.start > line.range.end
+                    || (wrapped && selection.start == line.range.end)
+                {
+                    continue;
+                }
+
+                let start = libm::roundf(state.x_in_line(line, selection.start.max(line.range.start))) as i32;
+                let mut end = libm::roundf(state.x_in_line(line, selection.end.min(line.range.end))) as i32;
+
+                // Selected line breaks are shown as a bit of extra space
+                if selection.end > line.range.end && !wrapped {
+                    end += line_height / 3;
+                }
+
+                pass.push(PreonShape::Rect {
+                    position: line_position(index) + PreonVector::new(start, 0),
+                    size: PreonVector::new(end - start, line_height),
+                    color: state.selection_color,
+                    index: None,
+                    radius: PreonCorners::ZERO,
+                });
+            }
+        }
+
+        if text.is_empty() {
+            if !state.placeholder.is_empty() {
+                pass.push(PreonShape::Text {
+                    text_style: self.style.text_style.clone(),
+                    color: state.placeholder_color,
+                    position: origin,
+                    size: PreonVector::new(content_size.x, line_height.max(content_size.y)),
+                    text: state.placeholder.clone(),
+                });
+            }
+        } else {
+            // Every line is drawn on its own, so the renderer doesn't wrap them differently
+            for index in visible_lines.clone() {
+                let line = &state.lines[index];
+
+                if line.range.is_empty() {
+                    continue;
+                }
+
+                // Extra room for differences between the measurer and the renderer, the text may never wrap
+                let width = libm::ceilf(line.width) as i32 + content_size.x;
+                let height = if state.multiline { line_height } else { line_height.max(content_size.y) };
+
+                pass.push(PreonShape::Text {
+                    text_style: self.style.text_style.clone(),
+                    color: self.get_foreground_color(),
+                    position: line_position(index),
+                    size: PreonVector::new(width, height),
+                    text: state.display_text(&text[line.range.clone()]),
+                });
+            }
+        }
+
+        // The composition is underlined on every line it's on
+        if state.is_composing() {
+            let preedit = state.caret..state.caret + state.preedit.len();
+
+            for index in visible_lines {
+                let line = &state.lines[index];
+                let start = preedit.start.max(line.range.start);
+                let end = preedit.end.min(line.range.end);
+
+                if start >= end {
+                    continue;
+                }
+
+                let start = libm::roundf(state.x_in_line(line, start)) as i32;
+                let end = libm::roundf(state.x_in_line(line, end)) as i32;
+
+                pass.push(PreonShape::Rect {
+                    position: line_position(index) + PreonVector::new(start, line_height - PREEDIT_UNDERLINE),
+                    size: PreonVector::new(end - start, PREEDIT_UNDERLINE),
+                    color: self.get_foreground_color(),
+                    index: None,
+                    radius: PreonCorners::ZERO,
+                });
+            }
+        }
+
+        // Input methods can hide the caret while composing
+        if self.focused && !state.has_selection() && !state.read_only && state.display_caret().is_some() {
+            if let Some(caret) = self.caret_rect() {
+                pass.push(PreonShape::Rect {
+                    position: caret.position,
+                    size: caret.size,
+                    color: self.get_foreground_color(),
+                    index: None,
+                    radius: PreonCorners::ZERO,
+                });
+            }
+        }
+    }
+
+    /// Where the caret of a text input is drawn, at the start of the composition while an input
+    /// method hides it. Input methods show their candidate window next to it, see
+    /// [`PreonEvent::ImeCursorArea`](crate::events::PreonEvent::ImeCursorArea).
+    pub(crate) fn caret_rect(&self) -> Option<PreonRect<i32>> {
+        let state = self.text_input.as_ref()?;
+        let caret = state.caret_position(state.display_caret().unwrap_or(state.caret));
+
+        Some(PreonRect::new(
+            self.text_origin() + PreonVector::new(libm::roundf(caret.x) as i32, libm::roundf(caret.y) as i32),
+            PreonVector::new(CARET_WIDTH, libm::ceilf(state.line_height) as i32),
+        ))
+    }
+}
+
+pub trait PreonComponentBuilderTextInputExtension {
+    /// A single line of editable text, with the caret placed at the end. The text scrolls
+    /// horizontally to keep the caret in view.
+    ///
+    /// While focused it takes the Left/Right/Home/End/Backspace/Delete keys (word-wise with Ctrl,
+    /// extending the selection with Shift), Ctrl+A, typed characters, Ctrl+C/X/V to copy, cut and paste
+    /// (see [`PreonEngine::set_clipboard`](crate::PreonEngine::set_clipboard)), and Ctrl+Z to undo and
+    /// Ctrl+Y or Ctrl+Shift+Z to redo edits. The mouse places the caret, selects by dragging, and
+    /// selects words and everything with double and triple clicks.
+    /// Emits [`PreonEvent::TextChanged`](crate::events::PreonEvent::TextChanged) for every edit, and
+    /// [`PreonEvent::Submitted`](crate::events::PreonEvent::Submitted) when pressing Return.
+    fn start_text_input(&mut self, text: String) -> &mut PreonComponentBuilder;
+    fn start_text_input_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder;
+    fn empty_text_input(&mut self, text: String) -> &mut PreonComponentBuilder;
+    fn empty_text_input_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder;
+    /// Shown while the text is empty.
+    fn placeholder(&mut self, placeholder: &str) -> &mut PreonComponentBuilder;
+    /// Hides the characters, see [`PreonTextInputState::password`].
+    fn password(&mut self, password: bool) -> &mut PreonComponentBuilder;
+    /// Maximum number of characters that can be typed.
+    fn max_length(&mut self, max_length: usize) -> &mut PreonComponentBuilder;
+}
+
+impl PreonComponentBuilderTextInputExtension for PreonComponentBuilder {
+    fn start_text_input(&mut self, text: String) -> &mut PreonComponentBuilder {
+        let inherited = self.inherited_style();
+        let padding = PreonBorder::from_xy(8, 4);
+        let line_height = libm::ceilf(inherited.text_style.size * 1.2) as i32;
+
+        self.stack.push(PreonComponent {
+            text_input: Some(Box::new(PreonTextInputState {
+                caret: text.len(),
+                anchor: text.len(),
+                ..Default::default()
+            })),
+            text,
+            style: PreonStyle {
+                layout: PreonLayout::Container,
+                background: PreonBackground::Color(PreonColor::from_rgba8(0xEE, 0xEE, 0xEE, 0xFF)),
+                hover: PreonStateStyle {
+                    background: Some(PreonBackground::Color(PreonColor::from_rgba8(0xE4, 0xE4, 0xE4, 0xFF))),
+                    ..Default::default()
+                },
+                focus: PreonStateStyle {
+                    background: Some(PreonBackground::Color(PreonColor::WHITE)),
+                    ..Default::default()
+                },
+                foreground_color: PreonColor::BLACK,
+                padding,
+                min_size: PreonVector::new(160, line_height + padding.y()),
+                corner_radius: PreonCorners::from_single(4.0),
+                overflow_x: PreonOverflow::Hidden,
+                overflow_y: PreonOverflow::Hidden,
+                ..inherited
+            },
+            mouse_events: true,
+            focusable: true,
+            ..Default::default()
+        });
+
+        self
+    }
+
+    fn start_text_input_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder {
+        self.start_text_input(String::from_str(text).unwrap())
+    }
+
+    fn empty_text_input(&mut self, text: String) -> &mut PreonComponentBuilder {
+        self.start_text_input(text).end()
+    }
+
+    fn empty_text_input_str(&mut self, text: &'static str) -> &mut PreonComponentBuilder {
+        self.start_text_input_str(text).end()
+    }
+
+    fn placeholder(&mut self, placeholder: &str) -> &mut PreonComponentBuilder {
+        match self.current_mut().text_input {
+            Some(ref mut state) => state.placeholder = String::from(placeholder),
+            None => log::warn!("placeholder() only works on text inputs"),
+        }
+
+        self
+    }
+
+    fn password(&mut self, password: bool) -> &mut PreonComponentBuilder {
+        match self.current_mut().text_input {
+            Some(ref mut state) => state.password = password,
+            None => log::warn!("password() only works on text inputs"),
+        }
+
+        self
+    }
+
+    fn max_length(&mut self, max_length: usize) -> &mut PreonComponentBuilder {
+        match self.current_mut().text_input {
+            Some(ref mut state) => state.max_length = Some(max_length),
+            None => log::warn!("max_length() only works on text inputs"),
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::{PreonCaretMotion, PreonTextInputState};
+    use crate::{
+        clipboard::{PreonClipboard, PreonMemoryClipboard},
+        rendering::PreonApproximateTextMeasurer,
+        style::PreonTextStyle,
+        types::PreonVector,
+    };
+
+    /// A state with the caret at the end of `text`, measured like a text input of `size`.
+    fn state_for(text: &str, size: PreonVector<i32>) -> PreonTextInputState {
+        let mut state = PreonTextInputState::default();
+        state.set_caret(text.len(), false);
+        measure(&mut state, text, size);
+
+        state
+    }
+
+    /// Same as [`state_for`], for a text area.
+    fn multiline_state_for(text: &str, size: PreonVector<i32>) -> PreonTextInputState {
+        let mut state = PreonTextInputState {
+            multiline: true,
+            ..Default::default()
+        };
+        state.set_caret(text.len(), false);
+        measure(&mut state, text, size);
+
+        state
+    }
+
+    fn measure(state: &mut PreonTextInputState, text: &str, size: PreonVector<i32>) {
+        state.measure(text, &PreonTextStyle::default(), &PreonApproximateTextMeasurer, size);
+    }
+
+    #[test]
+    fn external_changes_clear_the_history() {
+        let mut text = String::from("hello");
+        let mut state = state_for(&text, PreonVector::new(400, 20));
+
+        assert!(state.insert(&mut text, " world", false));
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "hello");
+
+        // Replaced without going through the text input, the redo would write past the end
+        text = String::from("hi");
+        assert!(!state.redo(&mut text));
+        assert_eq!(text, "hi");
+
+        assert!(state.insert(&mut text, "!", false));
+        text = String::from("bye");
+        measure(&mut state, &text, PreonVector::new(400, 20));
+        assert!(!state.undo(&mut text));
+        assert_eq!(text, "bye");
+    }
+
+    #[test]
+    fn max_length_counts_characters() {
+        let mut text = String::new();
+        let mut state = PreonTextInputState {
+            max_length: Some(4),
+            ..Default::default()
+        };
+
+        assert!(state.insert(&mut text, "héllo", false));
+        assert_eq!(text, "héll");
+        assert_eq!(state.caret(), 5);
+        assert!(!state.insert(&mut text, "ü", false));
+
+        // Replacing the selection makes room
+        state.select(&text, 1, 3);
+        assert!(state.insert(&mut text, "日本", false));
+        assert_eq!(text, "h日ll");
+
+        state.select(&text, 0, text.len());
+        assert!(state.insert(&mut text, "🦀🦀🦀🦀🦀", false));
+        assert_eq!(text, "🦀🦀🦀🦀");
+    }
+
+    #[test]
+    fn caret_moves_by_character_and_word() {
+        let text = "hello, wörld";
+        let mut state = state_for(text, PreonVector::new(400, 20));
+
+        state.move_caret(text, PreonCaretMotion::Left, false);
+        assert_eq!(state.caret(), 12);
+        state.set_caret(10, false);
+        // Never inside of a character
+        state.move_caret(text, PreonCaretMotion::Left, false);
+        assert_eq!(state.caret(), 8);
+        state.move_caret(text, PreonCaretMotion::Right, false);
+        assert_eq!(state.caret(), 10);
+
+        // Punctuation counts as a word of its own
+        state.move_caret(text, PreonCaretMotion::WordLeft, false);
+        assert_eq!(state.caret(), 7);
+        state.move_caret(text, PreonCaretMotion::WordLeft, false);
+        assert_eq!(state.caret(), 5);
+        state.move_caret(text, PreonCaretMotion::WordLeft, false);
+        assert_eq!(state.caret(), 0);
+        state.move_caret(text, PreonCaretMotion::WordRight, false);
+        assert_eq!(state.caret(), 5);
+        state.move_caret(text, PreonCaretMotion::WordRight, true);
+        assert_eq!(state.selection(), 5..7);
+        state.move_caret(text, PreonCaretMotion::WordRight, true);
+        assert_eq!(state.selection(), 5..13);
+
+        // Without extending, the caret jumps to the side of the selection it moves to
+        state.move_caret(text, PreonCaretMotion::WordLeft, false);
+        assert_eq!(state.selection(), 5..5);
+    }
+
+    #[test]
+    fn passwords_move_by_the_whole_text() {
+        let text = "hunter two";
+        let mut state = PreonTextInputState {
+            password: true,
+            ..Default::default()
+        };
+        state.set_caret(3, false);
+
+        state.move_caret(text, PreonCaretMotion::WordRight, false);
+        assert_eq!(state.caret(), text.len());
+        state.move_caret(text, PreonCaretMotion::WordLeft, false);
+        assert_eq!(state.caret(), 0);
+    }
+
+    #[test]
+    fn caret_moves_by_wrapped_line() {
+        // Wraps into "aaaa ", "a " and "aaaa"
+        let text = "aaaa a aaaa";
+        let mut state = multiline_state_for(text, PreonVector::new(42, 200));
+        assert_eq!(state.lines.len(), 3);
+
+        state.set_caret(6, false);
+        state.move_caret(text, PreonCaretMotion::LineStart, false);
+        assert_eq!(state.caret(), 5);
+        state.move_caret(text, PreonCaretMotion::LineEnd, false);
+        assert_eq!(state.caret(), 6);
+
+        state.move_caret(text, PreonCaretMotion::Down, false);
+        assert_eq!(state.line_of(state.caret()), 2);
+        state.move_caret(text, PreonCaretMotion::LineEnd, false);
+        assert_eq!(state.caret(), text.len());
+        state.move_caret(text, PreonCaretMotion::Home, false);
+        assert_eq!(state.caret(), 0);
+        state.move_caret(text, PreonCaretMotion::End, true);
+        assert_eq!(state.selection(), 0..text.len());
+    }
+
+    #[test]
+    fn up_and_down_keep_the_column() {
+        let text = "aaaa a aaaa";
+        let mut state = multiline_state_for(text, PreonVector::new(42, 200));
+        state.set_caret(3, false);
+
+        // The short line in between moves the caret to its end, the next line goes back to the column
+        state.move_caret(text, PreonCaretMotion::Down, false);
+        assert_eq!(state.caret(), 6);
+        state.move_caret(text, PreonCaretMotion::Down, false);
+        assert_eq!(state.caret(), 10);
+        state.move_caret(text, PreonCaretMotion::Up, false);
+        state.move_caret(text, PreonCaretMotion::Up, false);
+        assert_eq!(state.caret(), 3);
+
+        // Moving past the first or last line goes to the start or end of the text
+        state.move_caret(text, PreonCaretMotion::Up, false);
+        assert_eq!(state.caret(), 0);
+        state.move_caret(text, PreonCaretMotion::PageDown, false);
+        assert_eq!(state.caret(), text.len());
+
+        // Any other motion forgets the column
+        state.move_caret(text, PreonCaretMotion::Left, false);
+        assert_eq!(state.preferred_x, None);
+    }
+
+    #[test]
+    fn caret_moves_by_page() {
+        let text = "a\nb\nc\nd\ne\nf\ng";
+        // Three lines fit into the text area
+        let mut state = multiline_state_for(text, PreonVector::new(400, 60));
+        state.set_caret(0, false);
+
+        state.move_caret(text, PreonCaretMotion::PageDown, false);
+        assert_eq!(state.caret(), 6);
+        state.move_caret(text, PreonCaretMotion::PageDown, true);
+        assert_eq!(state.selection(), 6..12);
+        state.move_caret(text, PreonCaretMotion::PageUp, false);
+        assert_eq!(state.caret(), 6);
+        state.move_caret(text, PreonCaretMotion::PageUp, false);
+        assert_eq!(state.caret(), 0);
+    }
+
+    #[test]
+    fn typing_is_undone_word_by_word() {
+        let mut text = String::new();
+        let mut state = state_for(&text, PreonVector::new(400, 20));
+
+        for ch in ["h", "e", "y", " ", "y", "o", "u"] {
+            assert!(state.insert(&mut text, ch, true));
+        }
+        assert_eq!(text, "hey you");
+
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "hey ");
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "");
+        assert!(!state.undo(&mut text));
+
+        assert!(state.redo(&mut text));
+        assert!(state.redo(&mut text));
+        assert_eq!(text, "hey you");
+        assert!(!state.redo(&mut text));
+
+        // Edits that don't merge (e.g. pasting) are undone on their own
+        assert!(state.insert(&mut text, "!", true));
+        assert!(state.insert(&mut text, "!!", false));
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "hey you!");
+    }
+
+    #[test]
+    fn deleting_is_undone_at_once() {
+        let mut text = String::from("one two");
+        let mut state = state_for(&text, PreonVector::new(400, 20));
+
+        // Backspace, then Delete from the middle
+        assert!(state.delete(&mut text, PreonCaretMotion::Left));
+        assert!(state.delete(&mut text, PreonCaretMotion::Left));
+        assert_eq!(text, "one t");
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "one two");
+        assert_eq!(state.caret(), 7);
+
+        state.set_caret(1, false);
+        assert!(state.delete(&mut text, PreonCaretMotion::Right));
+        assert!(state.delete(&mut text, PreonCaretMotion::Right));
+        assert_eq!(text, "o two");
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "one two");
+
+        // Nothing left of the start
+        state.set_caret(0, false);
+        assert!(!state.delete(&mut text, PreonCaretMotion::Left));
+
+        // Typing after deleting starts a new edit
+        state.set_caret(7, false);
+        assert!(state.delete(&mut text, PreonCaretMotion::Left));
+        assert!(state.insert(&mut text, "x", true));
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "one tw");
+    }
+
+    #[test]
+    fn copy_cut_and_paste() {
+        let mut clipboard = PreonMemoryClipboard::new();
+        let mut text = String::from("hello world");
+        let mut state = state_for(&text, PreonVector::new(400, 20));
+
+        // Nothing to copy without a selection
+        assert!(!state.copy(&mut text, &mut clipboard, false));
+        assert_eq!(clipboard.get_text(), None);
+
+        state.select(&text, 0, 5);
+        assert!(!state.copy(&mut text, &mut clipboard, false));
+        assert_eq!(clipboard.get_text().as_deref(), Some("hello"));
+        assert_eq!(text, "hello world");
+
+        assert!(state.copy(&mut text, &mut clipboard, true));
+        assert_eq!(text, " world");
+
+        state.set_caret(text.len(), false);
+        clipboard.set_text(String::from("\r\nagain\nand"));
+        assert!(state.paste(&mut text, &mut clipboard));
+        assert_eq!(text, " world again and");
+
+        assert!(state.undo(&mut text));
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "hello world");
+    }
+
+    #[test]
+    fn text_areas_paste_line_breaks() {
+        let mut clipboard = PreonMemoryClipboard::new();
+        let mut text = String::new();
+        let mut state = multiline_state_for(&text, PreonVector::new(400, 200));
+
+        clipboard.set_text(String::from("a\r\nb"));
+        assert!(state.paste(&mut text, &mut clipboard));
+        assert_eq!(text, "a\nb");
+    }
+
+    #[test]
+    fn passwords_are_not_copied() {
+        let mut clipboard = PreonMemoryClipboard::new();
+        let mut text = String::from("secret");
+        let mut state = PreonTextInputState {
+            password: true,
+            ..Default::default()
+        };
+
+        state.select(&text, 0, text.len());
+        assert!(!state.copy(&mut text, &mut clipboard, true));
+        assert_eq!(clipboard.get_text(), None);
+        assert_eq!(text, "secret");
+    }
+
+    #[test]
+    fn preedit_is_shown_at_the_caret() {
+        let text = "ab";
+        let mut state = state_for(text, PreonVector::new(400, 20));
+        state.set_caret(1, false);
+
+        state.set_preedit(String::from("日本"), Some(6..6));
+        measure(&mut state, text, PreonVector::new(400, 20));
+        assert!(state.is_composing());
+        assert_eq!(state.compose(text), "a日本b");
+        assert_eq!(state.display_caret(), Some(7));
+        // Every character of the composition gets a caret position
+        assert_eq!(state.carets.len(), 5);
+
+        // Offsets inside of a character move back to its start, offsets past the end to the end
+        state.set_preedit(String::from("日本"), Some(4..20));
+        assert_eq!(state.preedit_cursor, Some(3..6));
+        assert_eq!(state.display_caret(), Some(4));
+
+        // Hidden cursor
+        state.set_preedit(String::from("日本"), None);
+        assert_eq!(state.display_caret(), None);
+    }
+
+    #[test]
+    fn preedit_is_committed() {
+        let mut text = String::from("ab");
+        let mut state = state_for(&text, PreonVector::new(400, 20));
+        state.set_caret(1, false);
+
+        state.set_preedit(String::from("にほん"), Some(9..9));
+        state.set_preedit(String::from("日本"), Some(6..6));
+        assert_eq!(text, "ab");
+
+        state.set_preedit(String::new(), None);
+        assert!(state.insert(&mut text, "日本", false));
+        assert_eq!(text, "a日本b");
+        assert_eq!(state.caret(), 7);
+        assert!(!state.is_composing());
+
+        assert!(state.undo(&mut text));
+        assert_eq!(text, "ab");
+    }
+
+    #[test]
+    fn preedit_is_cancelled() {
+        let text = "ab";
+        let mut state = state_for(text, PreonVector::new(400, 20));
+
+        state.set_preedit(String::from("x"), Some(1..1));
+        measure(&mut state, text, PreonVector::new(400, 20));
+        assert_eq!(state.carets.len(), 4);
+
+        state.set_preedit(String::new(), None);
+        measure(&mut state, text, PreonVector::new(400, 20));
+        assert!(!state.is_composing());
+        assert_eq!(state.compose(text), "ab");
+        assert_eq!(state.display_caret(), Some(2));
+        assert_eq!(state.carets.len(), 3);
+    }
+}
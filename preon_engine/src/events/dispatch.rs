@@ -0,0 +1,142 @@
+use alloc::boxed::Box;
+
+use crate::{
+    events::{PreonButtonState, PreonKeyEvent, PreonMouseButton, PreonMouseEvent, PreonScrollEvent},
+    PreonComponentHandle, PreonEngine,
+};
+
+/// Pointer and keyboard events sent to a specific component, see [`PreonEngine::dispatch`].
+#[derive(Debug, Clone)]
+pub enum PreonComponentEvent {
+    /// A mouse button was pressed or released, see [`PreonMouseEvent::target`].
+    MouseInput(PreonMouseEvent),
+    /// Scrolled over a component, see [`PreonScrollEvent::target`].
+    Scroll(PreonScrollEvent),
+    /// Sent to the focused component, or to the root component if nothing has focus.
+    KeyboardInput(PreonKeyEvent),
+    /// Sent to the focused component, or to the root component if nothing has focus.
+    ReceivedCharacter(char),
+}
+
+/// Filters events for [`PreonEngine::on`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreonEventKind {
+    /// Any [`PreonComponentEvent::MouseInput`].
+    MouseInput,
+    /// The left mouse button was pressed.
+    Pressed,
+    /// The left mouse button was released, after being pressed on the same component.
+    Released,
+    Scroll,
+    KeyboardInput,
+    ReceivedCharacter,
+}
+
+impl PreonEventKind {
+    pub fn matches(self, event: &PreonComponentEvent) -> bool {
+        use PreonButtonState::*;
+        use PreonComponentEvent as Event;
+
+        match (self, event) {
+            (PreonEventKind::MouseInput, Event::MouseInput(_)) => true,
+            (PreonEventKind::Pressed, Event::MouseInput(mouse)) => {
+                mouse.button == PreonMouseButton::Left && matches!(mouse.state, Pressed)
+            }
+            (PreonEventKind::Released, Event::MouseInput(mouse)) => {
+                mouse.button == PreonMouseButton::Left && matches!(mouse.state, Released)
+            }
+            (PreonEventKind::Scroll, Event::Scroll(_)) => true,
+            (PreonEventKind::KeyboardInput, Event::KeyboardInput(_)) => true,
+            (PreonEventKind::ReceivedCharacter, Event::ReceivedCharacter(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Where a dispatched event currently is on its way through the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreonEventPhase {
+    /// Travelling from the root down to the parent of the target.
+    Capture,
+    /// Arrived at the target.
+    Target,
+    /// Travelling from the parent of the target back up to the root.
+    Bubble,
+}
+
+/// Passed to event listeners, see [`PreonEngine::add_event_listener`].
+pub struct PreonEventContext<'a> {
+    pub engine: &'a mut PreonEngine,
+    pub(crate) event: &'a PreonComponentEvent,
+    pub(crate) target: PreonComponentHandle,
+    pub(crate) current_target: PreonComponentHandle,
+    pub(crate) phase: PreonEventPhase,
+    pub(crate) propagation_stopped: bool,
+    pub(crate) handled: bool,
+}
+
+impl PreonEventContext<'_> {
+    #[inline]
+    pub fn event(&self) -> &PreonComponentEvent {
+        self.event
+    }
+
+    /// The component the event was dispatched to.
+    #[inline]
+    pub fn target(&self) -> PreonComponentHandle {
+        self.target
+    }
+
+    /// The component this listener was added to, the target itself or one of its ancestors.
+    #[inline]
+    pub fn current_target(&self) -> PreonComponentHandle {
+        self.current_target
+    }
+
+    #[inline]
+    pub fn phase(&self) -> PreonEventPhase {
+        self.phase
+    }
+
+    /// Prevents the event from reaching any further components. Other listeners on the current
+    /// component are still called.
+    #[inline]
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    #[inline]
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    /// Tells the engine the event has been taken care of, so its default behaviour is skipped
+    /// (e.g. focusing a pressed component, or moving focus when pressing Tab) and no
+    /// `PreonEvent::Component*` event is emitted for it. Propagation continues.
+    #[inline]
+    pub fn set_handled(&mut self) {
+        self.handled = true;
+    }
+
+    #[inline]
+    pub fn is_handled(&self) -> bool {
+        self.handled
+    }
+}
+
+pub(crate) struct PreonEventListener {
+    pub(crate) capture: bool,
+    pub(crate) callback: Box<dyn FnMut(&mut PreonEventContext)>,
+}
+
+impl PreonEventListener {
+    /// Returns true if this listener should be called during `phase`. Both capturing and bubbling
+    /// listeners are called on the target itself.
+    pub(crate) fn listens_to(&self, phase: PreonEventPhase) -> bool {
+        match phase {
+            PreonEventPhase::Capture => self.capture,
+            PreonEventPhase::Target => true,
+            PreonEventPhase::Bubble => !self.capture,
+        }
+    }
+}
@@ -1,8 +1,21 @@
 use alloc::{vec::Vec, string::String};
 
-use core::fmt::Display;
+use core::{
+    fmt::Display,
+    ops::{BitOr, Range},
+    time::Duration,
+};
 
-use crate::types::PreonVector;
+use crate::{
+    timers::PreonTimerId,
+    types::{PreonRect, PreonVector},
+    PreonComponentHandle,
+};
+
+mod dispatch;
+
+pub use dispatch::{PreonComponentEvent, PreonEventContext, PreonEventKind, PreonEventPhase};
+pub(crate) use dispatch::PreonEventListener;
 
 impl Display for PreonButtonState {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -24,7 +37,7 @@ pub enum PreonButtonState {
     Released,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PreonMouseButton {
     Left,
     Middle,
@@ -32,6 +45,139 @@ pub enum PreonMouseButton {
     Other(u16),
 }
 
+/// Modifier keys held down, tracked by the engine from keyboard input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PreonModifiers(u8);
+
+impl PreonModifiers {
+    pub const NONE: Self = Self(0);
+    pub const SHIFT: Self = Self(0b0001);
+    pub const CTRL: Self = Self(0b0010);
+    pub const ALT: Self = Self(0b0100);
+    /// The Windows key, or Command on macOS.
+    pub const LOGO: Self = Self(0b1000);
+
+    #[inline]
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    #[inline]
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+
+    #[inline]
+    pub fn shift(self) -> bool {
+        self.contains(Self::SHIFT)
+    }
+
+    #[inline]
+    pub fn ctrl(self) -> bool {
+        self.contains(Self::CTRL)
+    }
+
+    #[inline]
+    pub fn alt(self) -> bool {
+        self.contains(Self::ALT)
+    }
+
+    #[inline]
+    pub fn logo(self) -> bool {
+        self.contains(Self::LOGO)
+    }
+
+    /// The modifier `key` belongs to, if it is one.
+    pub fn from_key(key: PreonKeyCode) -> Option<Self> {
+        match key {
+            PreonKeyCode::LShift | PreonKeyCode::RShift => Some(Self::SHIFT),
+            PreonKeyCode::LControl | PreonKeyCode::RControl => Some(Self::CTRL),
+            PreonKeyCode::LAlt | PreonKeyCode::RAlt => Some(Self::ALT),
+            PreonKeyCode::LWin | PreonKeyCode::RWin => Some(Self::LOGO),
+            _ => None,
+        }
+    }
+}
+
+impl BitOr for PreonModifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A key pressed or released, see [`PreonEvent::KeyboardInput`].
+#[derive(Debug, Clone, Copy)]
+pub struct PreonKeyEvent {
+    pub key: PreonKeyCode,
+    /// Platform dependent code of the physical key, which doesn't depend on the keyboard layout.
+    pub scancode: u32,
+    pub state: PreonButtonState,
+    /// Set for presses sent again by the platform while the key is held down.
+    pub is_repeat: bool,
+    /// Includes `key` itself when pressing a modifier.
+    pub modifiers: PreonModifiers,
+}
+
+/// How far to scroll, as reported by the platform. Positive values scroll towards the top left of
+/// the content (e.g. turning a mouse wheel away from you).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreonScrollDelta {
+    /// Lines of text, most mouse wheels scroll 1 line per step.
+    Lines(PreonVector<f32>),
+    /// Exact amounts, sent by trackpads.
+    Pixels(PreonVector<f32>),
+}
+
+impl PreonScrollDelta {
+    /// The delta in pixels, with lines converted using `line_height`.
+    pub fn to_pixels(self, line_height: f32) -> PreonVector<f32> {
+        match self {
+            PreonScrollDelta::Lines(lines) => lines * line_height,
+            PreonScrollDelta::Pixels(pixels) => pixels,
+        }
+    }
+}
+
+/// Scrolling over a component, see [`PreonEvent::Scroll`].
+#[derive(Debug, Clone, Copy)]
+pub struct PreonScrollEvent {
+    /// The topmost component under the mouse, whether it receives mouse events or not. See
+    /// [`PreonEngine::component_at`](crate::PreonEngine::component_at).
+    pub target: PreonComponentHandle,
+    pub delta: PreonScrollDelta,
+    /// Relative to the top left corner of the window.
+    pub position: PreonVector<i32>,
+    pub modifiers: PreonModifiers,
+}
+
+/// A mouse button pressed or released on a component, see [`PreonEvent::ComponentMouseInput`].
+#[derive(Debug, Clone, Copy)]
+pub struct PreonMouseEvent {
+    /// The topmost component receiving mouse events under the mouse. Releases go to the component
+    /// the button was pressed on, even if the mouse left it.
+    pub target: PreonComponentHandle,
+    pub button: PreonMouseButton,
+    pub state: PreonButtonState,
+    /// Relative to the top left corner of the window.
+    pub position: PreonVector<i32>,
+    /// Relative to the top left corner of the border rect of `target`.
+    pub local_position: PreonVector<i32>,
+    /// 1 for a single click, 2 for a double click, and so on. Releases have the count of their press.
+    pub click_count: u32,
+    pub modifiers: PreonModifiers,
+}
+
 #[derive(Debug, Clone)]
 pub enum PreonEvent {
     WindowOpened,
@@ -39,22 +185,91 @@ pub enum PreonEvent {
     WindowClosed,
     Update,
     LayoutUpdate,
+    /// The left mouse button was pressed or released on a component receiving mouse events,
+    /// contains the id of that component (empty if it has none). See [`PreonEngine::hit_test`](crate::PreonEngine::hit_test).
     ComponentPressed(String, PreonButtonState),
-    MouseInput(PreonMouseButton, PreonButtonState),
-    KeyboardInput(PreonKeyCode, PreonButtonState),
+    /// The mouse moved onto a component receiving mouse events, or onto one of its descendants.
+    MouseEnter(PreonComponentHandle),
+    /// Sent for every component that got a [`PreonEvent::MouseEnter`], once the mouse leaves it.
+    MouseLeave(PreonComponentHandle),
+    /// A component gained keyboard focus, see [`PreonEngine::focus`](crate::PreonEngine::focus).
+    FocusGained(PreonComponentHandle),
+    /// Sent for the previously focused component, before the [`PreonEvent::FocusGained`] of the next one.
+    FocusLost(PreonComponentHandle),
+    MouseInput(PreonMouseButton, PreonButtonState, PreonModifiers),
+    KeyboardInput(PreonKeyEvent),
     ReceivedCharacter(char),
+    /// A shortcut was pressed, contains the name of its action. See [`PreonEngine::add_shortcut`](crate::PreonEngine::add_shortcut).
+    Action(String),
+    /// A mouse button was pressed or released on a component, unless a listener handled it.
+    /// See [`PreonEngine::dispatch`](crate::PreonEngine::dispatch).
+    ComponentMouseInput(PreonMouseEvent),
+    /// Scrolled over a component, unless a listener handled it or a scroll view scrolled.
+    /// Listeners of the component's ancestors get it too, see [`PreonEngine::dispatch`](crate::PreonEngine::dispatch).
+    Scroll(PreonScrollEvent),
+    /// Same as [`PreonEvent::KeyboardInput`], sent to the focused component. Tab is used for
+    /// focus navigation and never sent.
+    ComponentKeyboardInput(PreonComponentHandle, PreonKeyEvent),
+    /// Same as [`PreonEvent::ReceivedCharacter`], sent to the focused component.
+    ComponentReceivedCharacter(PreonComponentHandle, char),
+    /// The children of a component started or stopped sticking out of its content rect, see
+    /// [`PreonComponent::is_overflowing_horizontally`](crate::components::PreonComponent::is_overflowing_horizontally)
+    /// and [`PreonComponent::is_overflowing_vertically`](crate::components::PreonComponent::is_overflowing_vertically).
+    OverflowChanged(PreonComponentHandle),
+    /// A timer fired, see [`PreonEngine::set_timeout`](crate::PreonEngine::set_timeout) and
+    /// [`PreonEngine::set_interval`](crate::PreonEngine::set_interval).
+    Timer(PreonTimerId),
+    /// The text of a text input was edited by the user, see
+    /// [`PreonComponentBuilderTextInputExtension`](crate::components::text_input::PreonComponentBuilderTextInputExtension).
+    /// Not sent for [`PreonEngine::set_text`](crate::PreonEngine::set_text).
+    TextChanged(PreonComponentHandle),
+    /// Return was pressed in a text input.
+    Submitted(PreonComponentHandle),
+    /// Same as [`PreonUserEvent::ImePreedit`].
+    ImePreedit(String, Option<Range<usize>>),
+    /// Same as [`PreonUserEvent::ImeCommit`].
+    ImeCommit(String),
+    /// The caret of the focused text input moved, in window coordinates. Render modules should show
+    /// the candidate window of the input method next to it, and disable input methods when it's
+    /// `None` (no text input has focus).
+    ImeCursorArea(Option<PreonRect<i32>>),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum PreonUserEvent {
+    /// Time passed since the previous tick. Render modules should send this before every
+    /// `engine.update()`, it's used to detect double clicks, for inertial scrolling and timers.
+    Tick(Duration),
     WindowOpened,
     WindowResized(PreonVector<u32>),
     WindowClosed,
+    /// The window lost keyboard focus. Keys that are still held down are forgotten, since their
+    /// release won't be sent.
+    WindowFocusLost,
     ForceUpdate,
     MouseMove(PreonVector<i32>),
+    /// The mouse left the window.
+    MouseLeftWindow,
     MouseInput(PreonMouseButton, PreonButtonState),
-    KeyboardInput(PreonKeyCode, PreonButtonState),
+    MouseWheel(PreonScrollDelta),
+    KeyboardInput {
+        key: PreonKeyCode,
+        /// See [`PreonKeyEvent::scancode`].
+        scancode: u32,
+        state: PreonButtonState,
+    },
+    /// The modifier keys changed, for example while the window wasn't focused. Modifier key
+    /// presses sent as [`PreonUserEvent::KeyboardInput`] are tracked too.
+    ModifiersChanged(PreonModifiers),
     ReceivedCharacter(char),
+    /// Text being composed with an input method (e.g. for Japanese or Chinese), shown underlined at
+    /// the caret of the focused text input without being part of its text yet. Every preedit
+    /// replaces the previous one, an empty one ends the composition. The range is the cursor inside
+    /// of the composed text (in bytes), `None` hides the cursor.
+    ImePreedit(String, Option<Range<usize>>),
+    /// Text finished with an input method, inserted at the caret of the focused text input. Ends
+    /// the composition.
+    ImeCommit(String),
 }
 
 /// Contains a front- and backbuffer> Events get pushed onto
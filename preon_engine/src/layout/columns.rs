@@ -1,22 +1,25 @@
-use crate::{types::{PreonAlignment, PreonVector}, size, components::PreonComponent};
+use crate::{types::{PreonAlignment, PreonVector}, size, PreonComponentHandle, PreonEngine};
 
-use super::PreonLayoutProvider;
+use super::{fit_content, PreonLayoutProvider};
 
 pub(crate) struct PreonColumnsLayoutProvider;
 
-impl PreonLayoutProvider for PreonColumnsLayoutProvider {
-    fn layout(component: &mut PreonComponent) {
+impl PreonColumnsLayoutProvider {
+    /// The size the children need, how many of them expand horizontally, and the width of the ones
+    /// that don't.
+    fn gather(engine: &PreonEngine, children: &[PreonComponentHandle]) -> (PreonVector<i32>, i32, i32) {
         let mut height = 0;
         let mut width = 0;
         let mut expanding_children = 0;
         let mut leftover_width = 0;
     
         // Gather some data on the children
-        for child in component.children.iter() {
+        for &child in children.iter() {
+            let child = engine.get_component(child).unwrap();
             let s = child.get_outer_size();
     
             if child.style.has_size_flag(size::horizontal::EXPAND) {
-                width += child.style.min_size.x;
+                width += child.get_min_size().x;
                 expanding_children += 1;
             } else {
                 width += s.x;
@@ -26,26 +29,57 @@ impl PreonLayoutProvider for PreonColumnsLayoutProvider {
             if !child.style.has_size_flag(size::vertical::EXPAND) {
                 height = height.max(s.y);
             } else {
-                height = height.max(child.style.min_size.y);
+                height = height.max(child.get_min_size().y);
             }
         }
-    
+
+        (PreonVector::new(width, height), expanding_children, leftover_width)
+    }
+}
+
+impl PreonLayoutProvider for PreonColumnsLayoutProvider {
+    fn measure(
+        engine: &mut PreonEngine,
+        handle: PreonComponentHandle,
+        children: &[PreonComponentHandle],
+    ) {
+        let (needed, _, _) = Self::gather(engine, children);
+        fit_content(engine.get_component_mut(handle).unwrap(), needed);
+    }
+
+    fn arrange(
+        engine: &mut PreonEngine,
+        handle: PreonComponentHandle,
+        children: &[PreonComponentHandle],
+    ) {
+        let (needed, expanding_children, leftover_width) = Self::gather(engine, children);
+        let (width, height) = (needed.x, needed.y);
+
+        let component = engine.get_component_mut(handle).unwrap();
+
+        // The parent might have expanded it to less than its children need
+        fit_content(component, needed);
+
         let position = component.get_content_position();
         let mut size = component.get_content_size();
-    
-        if component.style.has_size_flag(size::horizontal::FIT) && size.x < width {
-            component.set_content_size_x(width);
+
+        // Clipped content isn't limited by the size of the component
+        if component.style.overflow_x.is_clipped() {
+            size.x = size.x.max(width);
         }
-        if component.style.has_size_flag(size::vertical::FIT) && size.y < height {
-            component.set_content_size_y(height);
+        if component.style.overflow_y.is_clipped() {
+            size.y = size.y.max(height);
         }
-    
-        size = component.get_content_size();
+
+        let align_items = component.style.align_items;
+        let cross_align_items = component.style.cross_align_items;
     
         // Correctly position everything
         let mut x = 0;
     
-        for child in component.children.iter_mut() {
+        for &child in children.iter() {
+            let child = engine.get_component_mut(child).unwrap();
+
             if child.style.has_size_flag(size::horizontal::EXPAND) {
                 child.set_outer_size_x((size.x - leftover_width) / expanding_children);
             }
@@ -58,7 +92,7 @@ impl PreonLayoutProvider for PreonColumnsLayoutProvider {
             let y_position: i32 = if child.style.has_size_flag(size::vertical::EXPAND) {
                 0
             } else {
-                match component.style.cross_align_items {
+                match cross_align_items {
                     PreonAlignment::Start => 0,
                     PreonAlignment::Center => size.y / 2 - child_size.y / 2,
                     PreonAlignment::End => size.y - child_size.y,
@@ -72,7 +106,7 @@ impl PreonLayoutProvider for PreonColumnsLayoutProvider {
             let x_position: i32 = if expanding_children > 0 {
                 x
             } else {
-                match component.style.align_items {
+                match align_items {
                     PreonAlignment::Start => x,
                     PreonAlignment::Center => size.x / 2 - width / 2 + x,
                     PreonAlignment::End => (size.x - width) + x,
@@ -1,16 +1,29 @@
-use crate::components::PreonComponent;
+use crate::{PreonComponentHandle, PreonEngine};
 
 use super::PreonLayoutProvider;
 
 pub(crate) struct PreonContainerLayoutProvider;
 
 impl PreonLayoutProvider for PreonContainerLayoutProvider {
-    fn layout(component: &mut PreonComponent) {
+    fn measure(
+        _engine: &mut PreonEngine,
+        _handle: PreonComponentHandle,
+        _children: &[PreonComponentHandle],
+    ) {
+    }
+
+    fn arrange(
+        engine: &mut PreonEngine,
+        handle: PreonComponentHandle,
+        children: &[PreonComponentHandle],
+    ) {
+        let component = engine.get_component(handle).unwrap();
         let position = component.get_content_position();
         let size = component.get_content_size();
-        for child in component.children.iter_mut() {
+        for &child in children.iter() {
+            let child = engine.get_component_mut(child).unwrap();
             child.set_outer_position(position);
             child.set_outer_size(size);
         }
     }
-}
\ No newline at end of file
+}
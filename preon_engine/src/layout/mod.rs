@@ -1,14 +1,61 @@
-use crate::components::PreonComponent;
+use crate::{components::PreonComponent, size, types::PreonVector, PreonComponentHandle, PreonEngine};
 
 pub mod rows;
 pub mod columns;
 pub mod container;
 
+/// Layout runs in two passes, so every component is only visited once per pass: `measure` goes
+/// from the leaves up, `arrange` from the root down.
 pub(crate) trait PreonLayoutProvider {
-    fn layout(component: &mut PreonComponent);
+    /// Grows `handle` to fit its children, which have already been measured.
+    fn measure(
+        engine: &mut PreonEngine,
+        handle: PreonComponentHandle,
+        children: &[PreonComponentHandle],
+    );
+
+    /// Positions (and sizes, if they expand) the children inside of the content rect of `handle`,
+    /// after its own rect has been arranged.
+    fn arrange(
+        engine: &mut PreonEngine,
+        handle: PreonComponentHandle,
+        children: &[PreonComponentHandle],
+    );
+}
+
+/// Grows the content of `component` to `needed` on the axes it fits its children on. Clipped axes
+/// don't grow, their content overflows instead.
+pub(crate) fn fit_content(component: &mut PreonComponent, needed: PreonVector<i32>) {
+    let content = component.get_content_size();
+
+    if component.style.has_size_flag(size::horizontal::FIT)
+        && !component.style.overflow_x.is_clipped()
+        && content.x < needed.x
+    {
+        component.set_content_size_x(needed.x);
+    }
+    if component.style.has_size_flag(size::vertical::FIT)
+        && !component.style.overflow_y.is_clipped()
+        && content.y < needed.y
+    {
+        component.set_content_size_y(needed.y);
+    }
+}
+
+/// Shrinks the content of `component` back to its minimum size on the axes [`fit_content`] grows,
+/// so components get smaller again when their children do.
+pub(crate) fn reset_fit(component: &mut PreonComponent) {
+    let min_size = component.get_min_size();
+
+    if component.style.has_size_flag(size::horizontal::FIT) && !component.style.overflow_x.is_clipped() {
+        component.set_inner_size_x(min_size.x);
+    }
+    if component.style.has_size_flag(size::vertical::FIT) && !component.style.overflow_y.is_clipped() {
+        component.set_inner_size_y(min_size.y);
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C)]
 pub enum PreonLayout {
     Rows,
@@ -1,22 +1,25 @@
-use crate::{size, components::PreonComponent, types::{PreonAlignment, PreonVector}};
+use crate::{size, types::{PreonAlignment, PreonVector}, PreonComponentHandle, PreonEngine};
 
-use super::PreonLayoutProvider;
+use super::{fit_content, PreonLayoutProvider};
 
 pub(crate) struct PreonRowsLayoutProvider;
 
-impl PreonLayoutProvider for PreonRowsLayoutProvider {
-    fn layout(component: &mut PreonComponent) {
+impl PreonRowsLayoutProvider {
+    /// The size the children need, how many of them expand vertically, and the height of the ones
+    /// that don't.
+    fn gather(engine: &PreonEngine, children: &[PreonComponentHandle]) -> (PreonVector<i32>, i32, i32) {
         let mut height = 0;
         let mut width = 0;
         let mut expanding_children = 0;
         let mut leftover_height = 0;
     
         // Gather some data on the children
-        for child in component.children.iter() {
+        for &child in children.iter() {
+            let child = engine.get_component(child).unwrap();
             let s = child.get_outer_size();
     
             if child.style.has_size_flag(size::vertical::EXPAND) {
-                height += child.style.min_size.y;
+                height += child.get_min_size().y;
                 expanding_children += 1;
             } else {
                 height += s.y;
@@ -26,26 +29,57 @@ impl PreonLayoutProvider for PreonRowsLayoutProvider {
             if !child.style.has_size_flag(size::horizontal::EXPAND) {
                 width = width.max(s.x);
             } else {
-                width = width.max(child.style.min_size.x);
+                width = width.max(child.get_min_size().x);
             }
         }
-    
+
+        (PreonVector::new(width, height), expanding_children, leftover_height)
+    }
+}
+
+impl PreonLayoutProvider for PreonRowsLayoutProvider {
+    fn measure(
+        engine: &mut PreonEngine,
+        handle: PreonComponentHandle,
+        children: &[PreonComponentHandle],
+    ) {
+        let (needed, _, _) = Self::gather(engine, children);
+        fit_content(engine.get_component_mut(handle).unwrap(), needed);
+    }
+
+    fn arrange(
+        engine: &mut PreonEngine,
+        handle: PreonComponentHandle,
+        children: &[PreonComponentHandle],
+    ) {
+        let (needed, expanding_children, leftover_height) = Self::gather(engine, children);
+        let (width, height) = (needed.x, needed.y);
+
+        let component = engine.get_component_mut(handle).unwrap();
+
+        // The parent might have expanded it to less than its children need
+        fit_content(component, needed);
+
         let position = component.get_content_position();
         let mut size = component.get_content_size();
-    
-        if component.style.has_size_flag(size::horizontal::FIT) && size.x < width {
-            component.set_content_size_x(width);
+
+        // Clipped content isn't limited by the size of the component
+        if component.style.overflow_x.is_clipped() {
+            size.x = size.x.max(width);
         }
-        if component.style.has_size_flag(size::vertical::FIT) && size.y < height {
-            component.set_content_size_y(height);
+        if component.style.overflow_y.is_clipped() {
+            size.y = size.y.max(height);
         }
-    
-        size = component.get_content_size();
+
+        let align_items = component.style.align_items;
+        let cross_align_items = component.style.cross_align_items;
     
         // Correctly position everything
         let mut y = 0;
     
-        for child in component.children.iter_mut() {
+        for &child in children.iter() {
+            let child = engine.get_component_mut(child).unwrap();
+
             if child.style.has_size_flag(size::vertical::EXPAND) {
                 child.set_outer_size_y((size.y - leftover_height) / expanding_children);
             }
@@ -58,7 +92,7 @@ impl PreonLayoutProvider for PreonRowsLayoutProvider {
             let x_position: i32 = if child.style.has_size_flag(size::horizontal::EXPAND) {
                 0
             } else {
-                match component.style.cross_align_items {
+                match cross_align_items {
                     PreonAlignment::Start => 0,
                     PreonAlignment::Center => size.x / 2 - child_size.x / 2,
                     PreonAlignment::End => size.x - child_size.x,
@@ -72,7 +106,7 @@ impl PreonLayoutProvider for PreonRowsLayoutProvider {
             let y_position: i32 = if expanding_children > 0 {
                 y
             } else {
-                match component.style.align_items {
+                match align_items {
                     PreonAlignment::Start => y,
                     PreonAlignment::Center => size.y / 2 - height / 2 + y,
                     PreonAlignment::End => (size.y - height) + y,
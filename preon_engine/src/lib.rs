@@ -56,7 +56,7 @@
 //!
 //! Official? | Crate name        | Description
 //! :--       |:--                |:--
-//! Yes       | preon_module_wgpu | Opens a window and draws the specified PreonRenderPass generated with `PreonEngine::render()`. Uses [WGPU](https://github.com/gfx-rs/wgpu/) as a rendering backend, so it is cross-platform with support for Vulkan, Metal, DirectX11, DirectX12 and OpenGLES3.
+//! Yes       | preon_module_wgpu | Opens a window and draws the PreonDisplayList generated by `PreonEngine::update()`. Uses [WGPU](https://github.com/gfx-rs/wgpu/) as a rendering backend, so it is cross-platform with support for Vulkan, Metal, DirectX11, DirectX12 and OpenGLES3.
 //!
 //! **Modules currently in development (names may change) are:**
 //!
@@ -70,18 +70,48 @@
 
 extern crate alloc;
 
-use core::cell::RefCell;
+use core::{cell::RefCell, ops::Range, time::Duration};
 
-use alloc::{rc::Rc, vec::Vec};
-use components::PreonComponent;
-use events::{PreonEvent, PreonEventEmitter, PreonUserEvent};
-use hashbrown::HashMap;
+use alloc::{boxed::Box, rc::Rc, string::String, vec, vec::Vec};
+use animation::{
+    PreonAnimatable, PreonAnimatedValue, PreonAnimation, PreonKeyframes, PreonTransition,
+};
+use clipboard::{PreonClipboard, PreonMemoryClipboard};
+use components::{
+    scroll_view::PreonScrollAxis,
+    text_input::{self, PreonCaretMotion},
+    PreonComponent,
+};
+use events::{
+    PreonButtonState, PreonComponentEvent, PreonEvent, PreonEventContext, PreonEventEmitter,
+    PreonEventKind, PreonEventListener, PreonEventPhase, PreonKeyCode, PreonKeyEvent,
+    PreonModifiers, PreonMouseButton, PreonMouseEvent, PreonScrollDelta, PreonScrollEvent,
+    PreonUserEvent,
+};
+use hashbrown::{hash_map::Entry, HashMap};
+use layout::{
+    columns::PreonColumnsLayoutProvider, container::PreonContainerLayoutProvider,
+    reset_fit, rows::PreonRowsLayoutProvider, PreonLayout, PreonLayoutProvider,
+};
 use rendering::{
-    IntoFont, IntoImage, PreonFont, PreonImage, PreonRenderPass, PreonRendererLoadOperations,
+    IntoFont, IntoImage, PreonApproximateTextMeasurer, PreonDisplayList, PreonDisplayStep,
+    PreonFont, PreonImage, PreonRendererLoadOperations, PreonShape, PreonTextMeasurer,
+};
+use selector::{PreonSelection, PreonSelector, PreonSelectorError};
+use shortcuts::{PreonKeyChord, PreonShortcutConflict};
+use storage::PreonComponentStorage;
+use style::{PreonOverflow, PreonStyle};
+use timers::{PreonTimer, PreonTimerId};
+use traversal::{
+    PreonAncestors, PreonBreadthFirst, PreonDepthFirst, PreonPostOrder, PreonSiblings,
 };
 
-use self::types::PreonVector;
+use self::types::{PreonRect, PreonVector};
 
+/// Style transitions and keyframe animations, see [`PreonEngine::animate`].
+pub mod animation;
+/// Copying and pasting text, see [`PreonEngine::set_clipboard`].
+pub mod clipboard;
 /// All default components.
 pub mod components;
 
@@ -97,7 +127,17 @@ pub mod types;
 pub mod layout;
 /// no_std replacements for math operations
 pub mod math;
+/// Look up components by id, class and position, CSS style.
+pub mod selector;
+/// Key chords bound to named actions, see [`PreonEngine::add_shortcut`].
+pub mod shortcuts;
+/// Generational slot map used to store components.
+pub mod storage;
 pub mod style;
+/// Timeouts and intervals measured with [`PreonUserEvent::Tick`], see [`PreonEngine::set_timeout`].
+pub mod timers;
+/// Iterators for walking the component tree.
+pub mod traversal;
 
 /// Size flags shortcuts.
 pub mod size {
@@ -135,6 +175,55 @@ pub mod size {
     pub const FIT_EXPAND: u8 = FIT + EXPAND;
 }
 
+/// Dirty flags, used by the engine to only relayout and repaint what has changed.
+/// See [`PreonEngine::mark_dirty`].
+pub mod dirty {
+    /// The component and all of its descendants need to be laid out again.
+    pub const LAYOUT: u8 = 0b00000001;
+
+    /// One of the descendants needs to be laid out again. The layout of the component itself is
+    /// recalculated, untouched children are skipped.
+    pub const CHILDREN: u8 = 0b00000010;
+
+    /// Only the appearance changed (colors, text), no relayout needed.
+    pub const PAINT: u8 = 0b00000100;
+}
+
+/// Presses closer together than this (in time and distance) count as a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+const DOUBLE_CLICK_DISTANCE: i32 = 4;
+
+/// Scroll distance of a single mouse wheel step, see [`events::PreonScrollDelta::Lines`].
+const SCROLL_LINE_HEIGHT: f32 = 40.0;
+
+/// How quickly inertial scrolling slows down, higher is faster.
+const INERTIA_FRICTION: f32 = 8.0;
+
+/// Inertial scrolling stops below this speed, in pixels per second.
+const MIN_INERTIA_VELOCITY: f32 = 10.0;
+
+/// Longer ticks are shortened, so inertial scrolling doesn't skip to the end after a pause.
+const MAX_INERTIA_STEP: Duration = Duration::from_millis(50);
+
+/// A scrollbar being dragged with the left mouse button.
+#[derive(Debug, Clone, Copy)]
+struct PreonScrollbarDrag {
+    handle: PreonComponentHandle,
+    axis: PreonScrollAxis,
+    /// Where the thumb was grabbed, from its start.
+    grab: i32,
+}
+
+/// The last mouse button press, used to count clicks.
+#[derive(Debug, Clone, Copy)]
+struct PreonClick {
+    target: PreonComponentHandle,
+    button: PreonMouseButton,
+    position: PreonVector<i32>,
+    time: Duration,
+    count: u32,
+}
+
 /// A container for all variables & functions needed for managing your UI at runtime.
 ///
 /// # Writing modules
@@ -170,25 +259,22 @@ pub mod size {
 ///
 /// ```no_run
 /// # fn main() {
-/// # let mut engine = PreonEngine::<NoComponentStack>::new(
+/// # let mut engine = PreonEngine::new();
+/// # let root = engine.root;
 /// # PreonComponentBuilder::new()
 /// #   .start_hbox()
 /// #       .expand()
-/// #       .start_panel()
+/// #       .start_panel_hex("#f00")
 /// #           .expand()
-/// #           .panel_color("#f00")
 /// #       .end()
-/// #       .start_panel()
+/// #       .start_panel_hex("#0f0")
 /// #           .expand()
-/// #           .panel_color("#0f0")
 /// #       .end()
-/// #       .start_panel()
+/// #       .start_panel_hex("#00f")
 /// #           .expand()
-/// #           .panel_color("#00f")
 /// #       .end()
 /// #   .end()
-/// # .build()
-/// # );
+/// # .build_into(&mut engine, root);
 /// # let mut user_events = PreonEventEmitter::<PreonUserEvent>::new();
 /// // After engine intialization
 /// let epic_module = EpicModule::new();
@@ -198,280 +284,2988 @@ pub mod size {
 /// # }
 /// ```
 pub struct PreonEngine {
-    pub components: HashMap<u64, PreonComponent>,
-    pub children_collections: HashMap<u64, Vec<PreonComponentHandle>>,
+    pub components: PreonComponentStorage,
     pub root: PreonComponentHandle,
-    pub next_id: u64,
+    /// Lookup table for [`PreonEngine::find_by_id`], kept up to date by the engine.
+    ids: HashMap<String, PreonComponentHandle>,
+    /// Set when a component is marked dirty, see [`PreonEngine::mark_dirty`].
+    relayout_requested: bool,
+    repaint_requested: bool,
+    /// Components marked with [`dirty::PAINT`], rendered into the display list on the next update.
+    paint_queue: Vec<PreonComponentHandle>,
+    /// Set when the tree structure changes, so the render order has to be recalculated.
+    reorder_requested: bool,
     /// The component tree
     // pub tree: PreonComponent,
     /// Will be filled with events after `engine.update()`. See [`PreonEventEmitter`] and [`PreonEvent`]
     pub events: PreonEventEmitter<PreonEvent>,
     /// The size of the viewport, title bar not included.
     pub window_inner_size: PreonVector<u32>,
-    /// Pass this to your renderer module of choice after executing `engine.update()`. See [`PreonEventEmitter`] and [`PreonShape`](`rendering::PreonShape`)
-    pub render_pass: PreonRenderPass,
+    /// Pass this to your renderer module of choice after executing `engine.update()`. See [`PreonDisplayList`] and [`PreonShape`](`rendering::PreonShape`)
+    pub display_list: PreonDisplayList,
     pub renderer_load_ops: PreonRendererLoadOperations,
     pub image_references: Vec<Rc<RefCell<usize>>>,
     pub font_references: Vec<Rc<RefCell<usize>>>,
 
     pub mouse_position: PreonVector<i32>,
+    /// Sum of all [`PreonUserEvent::Tick`]s.
+    time: Duration,
+    /// The components mouse buttons were pressed on, until they're released.
+    pressed: Vec<(PreonMouseButton, PreonComponentHandle)>,
+    last_click: Option<PreonClick>,
+    /// Hovered components receiving mouse events, outermost first.
+    hovered: Vec<PreonComponentHandle>,
+    mouse_in_window: bool,
+    /// The component receiving keyboard input, see [`PreonEngine::focus`].
+    focused: Option<PreonComponentHandle>,
+    /// Tracked from keyboard input, see [`PreonEngine::modifiers`].
+    modifiers: PreonModifiers,
+    /// Keys held down, to detect repeated key presses.
+    pressed_keys: Vec<PreonKeyCode>,
+    /// See [`PreonEngine::add_shortcut`], global shortcuts have no scope.
+    shortcuts: HashMap<(PreonKeyChord, Option<PreonComponentHandle>), String>,
+    /// See [`PreonEngine::add_event_listener`].
+    listeners: HashMap<PreonComponentHandle, Vec<PreonEventListener>>,
+    /// Scroll views moving by themselves, see [`PreonComponentBuilderScrollViewExtension::inertial_scrolling`](components::scroll_view::PreonComponentBuilderScrollViewExtension::inertial_scrolling).
+    scrolling: Vec<PreonComponentHandle>,
+    scrollbar_drag: Option<PreonScrollbarDrag>,
+    /// Set when components moved without a relayout, e.g. by scrolling.
+    hover_outdated: bool,
+    /// See [`PreonEngine::set_timeout`] and [`PreonEngine::set_interval`].
+    timers: Vec<PreonTimer>,
+    next_timer_id: u32,
+    /// Components with an animation state, see [`PreonEngine::animate`] and [`PreonStyle::transitions`].
+    animated: Vec<PreonComponentHandle>,
+    /// See [`PreonEngine::set_text_measurer`].
+    text_measurer: Box<dyn PreonTextMeasurer>,
+    /// See [`PreonEngine::set_clipboard`].
+    clipboard: Box<dyn PreonClipboard>,
+    /// Last sent with [`PreonEvent::ImeCursorArea`].
+    ime_cursor_area: Option<PreonRect<i32>>,
 }
 
 impl PreonEngine {
     pub fn new() -> Self {
-        let mut components = HashMap::new();
-        components.insert(0, PreonComponent::default());
+        let mut components = PreonComponentStorage::new();
+        let root = components.insert(PreonComponent::default());
 
         Self {
             components,
-            children_collections: HashMap::new(),
-            root: PreonComponentHandle::new(0),
-            next_id: 1,
+            root,
+            ids: HashMap::new(),
+            relayout_requested: false,
+            repaint_requested: false,
+            paint_queue: Vec::new(),
+            reorder_requested: true,
             events: PreonEventEmitter::new(),
             window_inner_size: PreonVector::zero(),
-            render_pass: PreonRenderPass::new(),
+            display_list: PreonDisplayList::new(),
             renderer_load_ops: PreonRendererLoadOperations::new(),
             image_references: Vec::new(),
             font_references: Vec::new(),
             mouse_position: PreonVector::zero(),
+            time: Duration::ZERO,
+            pressed: Vec::new(),
+            last_click: None,
+            hovered: Vec::new(),
+            mouse_in_window: false,
+            focused: None,
+            modifiers: PreonModifiers::NONE,
+            pressed_keys: Vec::new(),
+            shortcuts: HashMap::new(),
+            listeners: HashMap::new(),
+            scrolling: Vec::new(),
+            scrollbar_drag: None,
+            hover_outdated: false,
+            timers: Vec::new(),
+            next_timer_id: 0,
+            animated: Vec::new(),
+            text_measurer: Box::new(PreonApproximateTextMeasurer),
+            clipboard: Box::new(PreonMemoryClipboard::new()),
+            ime_cursor_area: None,
         }
     }
 
+    /// Appends `component` to the children of `parent`, returning its handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` is invalid.
     pub fn push_component(
         &mut self,
         parent: PreonComponentHandle,
-        component: PreonComponent,
+        mut component: PreonComponent,
     ) -> PreonComponentHandle {
+        assert!(self.components.contains(parent), "Invalid PreonComponentHandle");
+
         component.parent = Some(parent);
+        component.children.clear();
+        component.hovered = false;
+        component.focused = false;
 
-        let id = self.next_id;
-        self.next_id += 1;
+        let id = component.id.clone();
+        let handle = self.components.insert(component);
 
-        let handle = PreonComponentHandle::new(id);
-        self.components.insert(handle.id, component);
+        if let Some(id) = id {
+            self.register_id(id, handle);
+        }
 
         self.components
-            .get(&parent.id)
+            .get_mut(parent)
             .expect("Invalid PreonComponentHandle")
             .children
             .push(handle);
 
+        self.mark_dirty(handle, dirty::LAYOUT);
+        self.reorder_requested = true;
+
         handle
     }
 
+    /// Removes `handle` and all of its descendants from the tree, returning the removed component.
+    ///
+    /// Returns `None` if `handle` doesn't exist, or if it's the root component (which can't be removed).
+    pub fn remove_component(&mut self, handle: PreonComponentHandle) -> Option<PreonComponent> {
+        if handle == self.root || !self.components.contains(handle) {
+            return None;
+        }
+
+        let subtree: Vec<PreonComponentHandle> = self.depth_first(handle).collect();
+        let parent = self.get_component(handle).unwrap().parent;
+        let mut removed = None;
+
+        self.detach(handle);
+
+        if let Some(parent) = parent {
+            self.mark_dirty(parent, dirty::CHILDREN);
+        }
+
+        for current in subtree {
+            let component = self.components.remove(current).unwrap();
+            self.display_list.remove(current);
+            self.listeners.remove(&current);
+            self.scrolling.retain(|&scrolling| scrolling != current);
+            self.animated.retain(|&animated| animated != current);
+            self.shortcuts.retain(|&(_, scope), _| scope != Some(current));
+
+            if self.focused == Some(current) {
+                self.focused = None;
+                self.events.push(PreonEvent::FocusLost(current));
+            }
+
+            if let Some(ref id) = component.id {
+                if self.ids.get(id) == Some(&current) {
+                    self.ids.remove(id);
+                }
+            }
+
+            if current == handle {
+                removed = Some(component);
+            }
+        }
+
+        self.reorder_requested = true;
+
+        removed
+    }
+
+    /// Moves `handle` (and its descendants) to `new_parent`, inserting it as child number `index`.
+    /// An `index` past the end appends it as the last child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either handle is invalid, if `handle` is the root, or if `new_parent` is `handle`
+    /// itself or one of its descendants.
+    pub fn reparent(
+        &mut self,
+        handle: PreonComponentHandle,
+        new_parent: PreonComponentHandle,
+        index: usize,
+    ) {
+        // Everything is checked before touching the tree, so a failed move leaves it intact
+        assert!(
+            self.components.contains(handle) && self.components.contains(new_parent),
+            "Invalid PreonComponentHandle"
+        );
+        assert!(handle != self.root, "The root component can't be reparented");
+        assert!(
+            !self.is_ancestor_or_self(handle, new_parent),
+            "Can't reparent a component into its own subtree"
+        );
+
+        let old_parent = self.get_component(handle).unwrap().parent;
+
+        self.detach(handle);
+
+        if let Some(old_parent) = old_parent {
+            self.mark_dirty(old_parent, dirty::CHILDREN);
+        }
+
+        let siblings = &mut self.components.get_mut(new_parent).unwrap().children;
+        siblings.insert(index.min(siblings.len()), handle);
+
+        self.get_component_mut(handle).unwrap().parent = Some(new_parent);
+
+        self.mark_dirty(handle, dirty::LAYOUT);
+        self.reorder_requested = true;
+    }
+
+    /// Moves `handle` right before `sibling`, changing its parent if necessary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either handle is invalid, if `sibling` is the root, or for the same reasons as
+    /// [`PreonEngine::reparent`].
+    pub fn move_before(&mut self, handle: PreonComponentHandle, sibling: PreonComponentHandle) {
+        self.move_next_to(handle, sibling, 0);
+    }
+
+    /// Moves `handle` right after `sibling`, changing its parent if necessary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either handle is invalid, if `sibling` is the root, or for the same reasons as
+    /// [`PreonEngine::reparent`].
+    pub fn move_after(&mut self, handle: PreonComponentHandle, sibling: PreonComponentHandle) {
+        self.move_next_to(handle, sibling, 1);
+    }
+
+    /// Swaps the children at index `a` and `b` of `parent`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` is invalid, or if `a` or `b` are out of bounds.
+    pub fn swap_children(&mut self, parent: PreonComponentHandle, a: usize, b: usize) {
+        self.get_component_mut(parent)
+            .expect("Invalid PreonComponentHandle")
+            .children
+            .swap(a, b);
+
+        self.mark_dirty(parent, dirty::CHILDREN);
+        self.reorder_requested = true;
+    }
+
+    /// Copies `handle` and all of its descendants, and appends the copy to `new_parent`.
+    /// Returns the handle of the copied root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either handle is invalid.
+    pub fn clone_subtree(
+        &mut self,
+        handle: PreonComponentHandle,
+        new_parent: PreonComponentHandle,
+    ) -> PreonComponentHandle {
+        assert!(
+            self.components.contains(handle) && self.components.contains(new_parent),
+            "Invalid PreonComponentHandle"
+        );
+
+        // Collect first, so cloning into the subtree itself doesn't clone the copies too.
+        // Depth-first order guarantees parents are copied before their children.
+        let subtree: Vec<PreonComponentHandle> = self.depth_first(handle).collect();
+        let mut copies: HashMap<PreonComponentHandle, PreonComponentHandle> =
+            HashMap::with_capacity(subtree.len());
+
+        for original in subtree {
+            let component = self.get_component(original).unwrap().clone();
+            let parent = match component.parent {
+                Some(parent) if original != handle => copies[&parent],
+                _ => new_parent,
+            };
+
+            let copy = self.push_component(parent, component);
+            copies.insert(original, copy);
+        }
+
+        copies[&handle]
+    }
+
+    /// Returns `None` if `handle` doesn't exist, or if its component has been removed.
     pub fn get_component(&self, handle: PreonComponentHandle) -> Option<&PreonComponent> {
-        self.components.get(&handle.id)
+        self.components.get(handle)
     }
 
+    /// Changes made through the returned reference aren't tracked, call [`PreonEngine::mark_dirty`]
+    /// afterwards or use [`PreonEngine::set_text`] and [`PreonEngine::set_style`] instead.
     pub fn get_component_mut(
         &mut self,
         handle: PreonComponentHandle,
     ) -> Option<&mut PreonComponent> {
-        self.components.get_mut(&handle.id)
+        self.components.get_mut(handle)
     }
 
-    // pub fn set_tree(&mut self, tree: PreonComponent) {
-    //     self.tree = tree;
-    // }
+    /// Flags `handle` as changed, so the next `engine.update()` relays out and/or repaints it.
+    /// See [`dirty`] for the available flags.
+    ///
+    /// Only needed after changing a component through [`PreonEngine::get_component_mut`],
+    /// [`PreonEngine::set_text`], [`PreonEngine::set_style`], [`PreonEngine::edit_style`] and the
+    /// tree mutation functions already take care of this.
+    pub fn mark_dirty(&mut self, handle: PreonComponentHandle, flags: u8) {
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
 
-    pub fn load_image(&mut self, image: impl IntoImage) -> PreonImage {
-        self.renderer_load_ops.textures.push(image.get_image());
-        self.image_references
-            .push(Rc::new(RefCell::new(self.image_references.len())));
-        PreonImage::new(self.image_references[self.image_references.len() - 1].clone())
-    }
+        if flags & dirty::PAINT != 0 {
+            if component.dirty & dirty::PAINT == 0 {
+                self.paint_queue.push(handle);
+            }
 
-    pub fn unload_image(&mut self, image: PreonImage) {
-        let index = image.index();
+            self.repaint_requested = true;
+        }
 
-        self.renderer_load_ops.unload_textures.push(index);
-        self.image_references.remove(
-            self.image_references
-                .iter()
-                .position(|r| *r.borrow() == index)
-                .unwrap(),
-        );
+        component.dirty |= flags;
 
-        for image_ref in self.image_references.iter_mut() {
-            if *image_ref.borrow() > index {
-                *image_ref.borrow_mut() -= 1;
+        if flags & (dirty::LAYOUT | dirty::CHILDREN) != 0 {
+            self.relayout_requested = true;
+
+            // If a component already has the CHILDREN flag, so do all of its ancestors
+            let mut current = component.parent;
+            while let Some(ancestor) = current {
+                let ancestor = self.components.get_mut(ancestor).unwrap();
+
+                if ancestor.dirty & dirty::CHILDREN != 0 {
+                    break;
+                }
+
+                ancestor.dirty |= dirty::CHILDREN;
+                current = ancestor.parent;
             }
         }
     }
 
-    pub fn load_font(&mut self, font: impl IntoFont) -> PreonFont {
-        self.renderer_load_ops.fonts.push(font.get_font());
-        self.font_references
-            .push(Rc::new(RefCell::new(self.font_references.len())));
-        PreonFont::new(self.font_references[self.font_references.len() - 1].clone())
+    /// Changes the text of `handle`. Text isn't measured by the layout, so this only causes a repaint.
+    /// The caret of text inputs moves to the end of the new text, and their edits can't be undone anymore.
+    pub fn set_text(&mut self, handle: PreonComponentHandle, text: String) {
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
+
+        if component.text != text {
+            if let Some(ref mut state) = component.text_input {
+                state.set_caret(text.len(), false);
+                state.clear_history();
+            }
+
+            component.text = text;
+            self.mark_dirty(handle, dirty::PAINT);
+        }
     }
 
-    pub fn unload_font(&mut self, font: PreonFont) {
-        let index = font.index();
+    /// Selects the bytes in `selection` of the text input `handle`, placing the caret at the end.
+    /// The range is moved inside of the text and onto character boundaries.
+    pub fn select_text(&mut self, handle: PreonComponentHandle, selection: Range<usize>) {
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
 
-        self.renderer_load_ops.unload_fonts.push(index);
-        self.font_references.remove(
-            self.font_references
-                .iter()
-                .position(|r| *r.borrow() == index)
-                .unwrap(),
-        );
+        match component.text_input {
+            Some(ref mut state) => state.select(&component.text, selection.start, selection.end),
+            None => return log::warn!("select_text() only works on text inputs"),
+        }
 
-        for font_ref in self.font_references.iter_mut() {
-            if *font_ref.borrow() > index {
-                *font_ref.borrow_mut() -= 1;
-            }
+        self.mark_dirty(handle, dirty::PAINT);
+    }
+
+    /// Replaces the style of `handle`, only causing a relayout if a property affecting the layout
+    /// changed. See [`PreonStyle::layout_differs`](style::PreonStyle::layout_differs).
+    ///
+    /// Properties with a transition in the new style animate from their old value.
+    pub fn set_style(&mut self, handle: PreonComponentHandle, style: PreonStyle) {
+        // Remember the old values before they're replaced
+        self.track_transitions(handle, &style.transitions);
+
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
+        let old_style = core::mem::replace(&mut component.style, style);
+        let opacity_changed = old_style.opacity != component.style.opacity;
+
+        let flags = if old_style.layout_differs(&component.style) {
+            dirty::LAYOUT
+        } else {
+            dirty::PAINT
+        };
+
+        self.mark_dirty(handle, flags);
+
+        if opacity_changed {
+            self.mark_subtree_dirty(handle, dirty::PAINT);
         }
     }
 
-    pub fn update(&mut self, user_events: &PreonEventEmitter<PreonUserEvent>) -> bool {
-        if user_events.is_empty() && self.events.is_empty() {
-            return false;
+    /// Changes the style of `handle` in place, see [`PreonEngine::set_style`].
+    ///
+    /// ```ignore
+    /// engine.edit_style(label, |style| style.foreground_color = PreonColor::from_hex("#da0037"));
+    /// ```
+    pub fn edit_style<F: FnOnce(&mut PreonStyle)>(&mut self, handle: PreonComponentHandle, f: F) {
+        let mut style = self
+            .get_component(handle)
+            .expect("Invalid PreonComponentHandle")
+            .style
+            .clone();
+
+        f(&mut style);
+
+        self.set_style(handle, style);
+    }
+
+    /// Returns the component with id `id`, see [`PreonComponentBuilder::id`](components::PreonComponentBuilder::id).
+    pub fn find_by_id(&self, id: &str) -> Option<PreonComponentHandle> {
+        self.ids.get(id).copied()
+    }
+
+    /// Changes (or removes, if `id` is `None`) the id of `handle`.
+    ///
+    /// Ids are unique, if another component already uses `id` this component won't be found
+    /// with [`PreonEngine::find_by_id`] and a warning is logged.
+    pub fn set_id(&mut self, handle: PreonComponentHandle, id: Option<String>) {
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
+        let old_id = core::mem::replace(&mut component.id, id.clone());
+
+        if let Some(old_id) = old_id {
+            if self.ids.get(&old_id) == Some(&handle) {
+                self.ids.remove(&old_id);
+            }
         }
 
-        let mut update_layout = false;
+        if let Some(id) = id {
+            self.register_id(id, handle);
+        }
+    }
 
-        for event in user_events.take() {
-            match event {
-                PreonUserEvent::WindowResized(new_size) => {
-                    if new_size != self.window_inner_size {
-                        self.window_inner_size = new_size;
-                        self.events.push(PreonEvent::WindowResized(new_size));
-                    }
-                    update_layout = true
-                }
-                PreonUserEvent::ForceUpdate => update_layout = true,
-                PreonUserEvent::WindowOpened => {
-                    self.events.push(PreonEvent::WindowOpened);
-                    update_layout = true
-                }
-                PreonUserEvent::WindowClosed => {
-                    self.events.push(PreonEvent::WindowClosed);
-                }
-                PreonUserEvent::MouseMove(mouse_position) => {
-                    let tree = self.get_component_mut(self.root).unwrap();
-                    // if let Some(component) = tree.get_hovered_child(mouse_position) {}
-                    self.mouse_position = mouse_position;
-                }
-                PreonUserEvent::MouseInput(button, state) => {
-                    self.events.push(PreonEvent::MouseInput(button, state));
-
-                    match button {
-                        events::PreonMouseButton::Left => match state {
-                            events::PreonButtonState::Pressed => {
-                                let tree = self.get_component_mut(self.root).unwrap();
-
-                                // if let Some(component) = tree.get_hovered_child(self.mouse_position)
-                                // {
-                                //     if let Some(event) = component.trigger_pressed() {
-                                //         self.events.push(event);
-                                //     }
-                                // }
-                            }
-                            events::PreonButtonState::Released => (),
-                        },
-                        // events::PreonMouseButton::Middle => todo!(),
-                        // events::PreonMouseButton::Right => todo!(),
-                        // events::PreonMouseButton::Other(_) => todo!(),
-                        _ => (),
-                    }
-                }
-                PreonUserEvent::KeyboardInput(key, state) => {
-                    self.events.push(PreonEvent::KeyboardInput(key, state));
-                }
-                PreonUserEvent::ReceivedCharacter(ch) => {
-                    self.events.push(PreonEvent::ReceivedCharacter(ch))
-                }
+    fn register_id(&mut self, id: String, handle: PreonComponentHandle) {
+        match self.ids.entry(id) {
+            Entry::Occupied(entry) => {
+                log::warn!("Duplicate component id \"{}\", ignoring", entry.key())
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(handle);
             }
         }
+    }
 
-        if update_layout {
-            log::info!("Relayout");
+    /// Returns all components matching `selector` in tree order. See [`PreonSelector`] for the syntax.
+    ///
+    /// ```ignore
+    /// for row in engine.select(".list > .row:nth-child(odd)")? {
+    ///     engine.get_component_mut(row).unwrap().style.background = PreonBackground::Color(stripe);
+    /// }
+    /// ```
+    pub fn select(&self, selector: &str) -> Result<PreonSelection<'_>, PreonSelectorError> {
+        Ok(self.query(PreonSelector::parse(selector)?))
+    }
 
-            let mut tree = self.get_component_mut(self.root).unwrap();
+    /// Returns the first component matching `selector` in tree order, if any.
+    pub fn select_first(
+        &self,
+        selector: &str,
+    ) -> Result<Option<PreonComponentHandle>, PreonSelectorError> {
+        Ok(self.select(selector)?.next())
+    }
 
-            tree.set_outer_size(PreonVector::new(
-                self.window_inner_size.x as i32,
-                self.window_inner_size.y as i32,
-            ));
-            tree.set_outer_position(PreonVector::zero());
+    /// Same as [`PreonEngine::select`], but with a selector that has already been parsed.
+    pub fn query(&self, selector: PreonSelector) -> PreonSelection<'_> {
+        PreonSelection::new(self, selector)
+    }
 
-            tree.layout();
-            tree.render(&mut self.render_pass);
+    // pub fn set_tree(&mut self, tree: PreonComponent) {
+    //     self.tree = tree;
+    // }
 
-            self.events.push(PreonEvent::LayoutUpdate);
-            self.render_pass.flip();
-        }
+    /// Returns true if `ancestor` is `handle`, or one of its (grand)parents.
+    pub fn is_ancestor_or_self(
+        &self,
+        ancestor: PreonComponentHandle,
+        handle: PreonComponentHandle,
+    ) -> bool {
+        handle == ancestor || self.ancestors(handle).any(|a| a == ancestor)
+    }
 
-        self.events.push(PreonEvent::Update);
-        self.events.flip();
+    /// `handle` and all of its descendants, parents before their children.
+    pub fn depth_first(&self, handle: PreonComponentHandle) -> PreonDepthFirst<'_> {
+        PreonDepthFirst::new(self, handle)
+    }
 
-        true
+    /// `handle` and all of its descendants, children before their parents.
+    pub fn post_order(&self, handle: PreonComponentHandle) -> PreonPostOrder<'_> {
+        PreonPostOrder::new(self, handle)
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct PreonComponentHandle {
-    pub(crate) id: u64,
-}
+    /// `handle` and all of its descendants, level by level.
+    pub fn breadth_first(&self, handle: PreonComponentHandle) -> PreonBreadthFirst<'_> {
+        PreonBreadthFirst::new(self, handle)
+    }
 
-impl PreonComponentHandle {
-    pub fn new(id: u64) -> Self {
-        Self { id }
+    /// The parent of `handle`, its parent, and so on up to the root.
+    pub fn ancestors(&self, handle: PreonComponentHandle) -> PreonAncestors<'_> {
+        PreonAncestors::new(self, handle)
     }
-}
 
-/// Contains all the necessary imports to quickly build an app with PreonEngine
-pub mod prelude {
-    pub use crate::components::button::PreonComponentBuilderButtonExtension;
-    pub use crate::components::hbox::PreonComponentBuilderHBoxExtension;
-    pub use crate::components::label::PreonComponentBuilderLabelExtension;
-    pub use crate::components::panel::PreonComponentBuilderPanelExtension;
-    pub use crate::components::static_texture::PreonComponentBuilderStaticTextureExtension;
-    pub use crate::components::vbox::PreonComponentBuilderVBoxExtension;
-    pub use crate::components::PreonComponentBuilder;
-    pub use crate::events::PreonEvent;
-    pub use crate::events::PreonUserEvent;
-    pub use crate::rendering::PreonFont;
-    pub use crate::rendering::PreonImage;
-    pub use crate::size;
-    pub use crate::style::PreonBackground;
-    pub use crate::style::PreonClass;
-    pub use crate::style::PreonComponentBuilderStyleExtension;
-    pub use crate::style::PreonComponentBuilderTextStyleExtension;
-    pub use crate::types::*;
-    pub use crate::PreonEngine;
-}
+    /// The other children of the parent of `handle`.
+    pub fn siblings(&self, handle: PreonComponentHandle) -> PreonSiblings<'_> {
+        PreonSiblings::new(self, handle)
+    }
 
-/// Replaces the log crate
-#[cfg(not(feature = "logging"))]
-pub mod log {
-    pub use crate::{error, info, log_enabled};
-    #[macro_export]
-    macro_rules! log_enabled {
-        (target: $target:expr, $lvl:expr) => {
-            false
-        };
-        ($lvl:expr) => {
-            false
-        };
+    /// All descendants of `handle` (`handle` itself excluded) in depth-first order.
+    pub fn descendants(
+        &self,
+        handle: PreonComponentHandle,
+    ) -> core::iter::Skip<PreonDepthFirst<'_>> {
+        self.depth_first(handle).skip(1)
     }
-    #[macro_export]
-    macro_rules! info {
-        (target: $target:expr, $($arg:tt)+) => {{};};
-        ($($arg:tt)+) => {{};};
+
+    /// All descendants of `handle` for which `predicate` returns true, in depth-first order.
+    ///
+    /// ```ignore
+    /// let clickable: Vec<_> = engine.descendants_where(engine.root, |c| c.mouse_events).collect();
+    /// ```
+    pub fn descendants_where<'a, P>(
+        &'a self,
+        handle: PreonComponentHandle,
+        mut predicate: P,
+    ) -> impl Iterator<Item = PreonComponentHandle> + 'a
+    where
+        P: FnMut(&PreonComponent) -> bool + 'a,
+    {
+        self.descendants(handle)
+            .filter(move |&descendant| predicate(self.get_component(descendant).unwrap()))
     }
-    #[macro_export]
-    macro_rules! warn {
-        (target: $target:expr, $($arg:tt)+) => {{};};
-        ($($arg:tt)+) => {{};};
+
+    /// Returns the topmost component at `point` that receives mouse events (see
+    /// [`PreonComponentBuilder::receive_events`](components::PreonComponentBuilder::receive_events)),
+    /// components rendered later are on top. Points outside of the window never hit anything.
+    pub fn hit_test(&self, point: PreonVector<i32>) -> Option<PreonComponentHandle> {
+        self.hit_test_window(point, false)
     }
-    #[macro_export]
-    macro_rules! error {
-        (target: $target:expr, $($arg:tt)+) => {{};};
-        ($($arg:tt)+) => {{};};
+
+    /// Same as [`PreonEngine::hit_test`], but also returns components that don't receive mouse events.
+    pub fn component_at(&self, point: PreonVector<i32>) -> Option<PreonComponentHandle> {
+        self.hit_test_window(point, true)
     }
-}
 
-#[cfg(feature = "logging")]
-pub use log;
+    fn hit_test_window(&self, point: PreonVector<i32>, any: bool) -> Option<PreonComponentHandle> {
+        let window = PreonRect::new(
+            PreonVector::zero(),
+            PreonVector::new(
+                self.window_inner_size.x as i32,
+                self.window_inner_size.y as i32,
+            ),
+        );
+
+        if window.contains_point(point) {
+            self.hit_test_component(self.root, point, any)
+        } else {
+            None
+        }
+    }
+
+    fn hit_test_component(
+        &self,
+        handle: PreonComponentHandle,
+        point: PreonVector<i32>,
+        any: bool,
+    ) -> Option<PreonComponentHandle> {
+        let component = self.get_component(handle)?;
+
+        // Clipped children can only be hit inside of the clip
+        let in_clip = match component.get_clip_rect() {
+            Some(clip) => clip.contains_point(point),
+            None => true,
+        };
+
+        if in_clip {
+            // Children are rendered after their parent, and the last child on top of its siblings
+            for &child in component.children.iter().rev() {
+                if let Some(hit) = self.hit_test_component(child, point, any) {
+                    return Some(hit);
+                }
+            }
+        }
+
+        if (any || component.mouse_events) && component.get_border_rect().contains_point(point) {
+            Some(handle)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the hovered components (see [`PreonComponent::is_hovered`]), outermost first.
+    pub fn hovered(&self) -> &[PreonComponentHandle] {
+        &self.hovered
+    }
+
+    /// Recalculates the hovered components, emitting `MouseEnter` and `MouseLeave` events.
+    fn update_hover(&mut self) {
+        let target = if self.mouse_in_window {
+            self.hit_test(self.mouse_position)
+        } else {
+            None
+        };
+
+        let mut hovered: Vec<PreonComponentHandle> = match target {
+            Some(target) => core::iter::once(target)
+                .chain(self.ancestors(target))
+                .filter(|&handle| self.get_component(handle).unwrap().mouse_events)
+                .collect(),
+            None => Vec::new(),
+        };
+        hovered.reverse();
+
+        if hovered == self.hovered {
+            return;
+        }
+
+        let old_hovered = core::mem::replace(&mut self.hovered, hovered);
+
+        // Innermost components are left first, and entered last
+        for &handle in old_hovered.iter().rev() {
+            if !self.hovered.contains(&handle) {
+                self.set_hovered(handle, false);
+                self.events.push(PreonEvent::MouseLeave(handle));
+            }
+        }
+
+        for index in 0..self.hovered.len() {
+            let handle = self.hovered[index];
+
+            if !old_hovered.contains(&handle) {
+                self.set_hovered(handle, true);
+                self.events.push(PreonEvent::MouseEnter(handle));
+            }
+        }
+    }
+
+    fn set_hovered(&mut self, handle: PreonComponentHandle, hovered: bool) {
+        // The component might have been removed while hovered
+        if let Some(component) = self.components.get_mut(handle) {
+            component.hovered = hovered;
+            self.mark_dirty(handle, dirty::PAINT);
+        }
+    }
+
+    /// Returns the component receiving keyboard input, if any. Key events are sent to it as
+    /// [`PreonEvent::ComponentKeyboardInput`] and [`PreonEvent::ComponentReceivedCharacter`].
+    pub fn focused(&self) -> Option<PreonComponentHandle> {
+        self.focused
+    }
+
+    /// Gives `handle` keyboard focus, emitting `FocusLost` for the previously focused component and
+    /// `FocusGained` for `handle`. Components that aren't focusable (see
+    /// [`PreonComponentBuilder::focusable`](components::PreonComponentBuilder::focusable)) are ignored.
+    ///
+    /// Pressing a component receiving mouse events also focuses it, or its closest focusable ancestor.
+    pub fn focus(&mut self, handle: PreonComponentHandle) {
+        let component = self
+            .get_component(handle)
+            .expect("Invalid PreonComponentHandle");
+
+        if component.focusable {
+            self.set_focused(Some(handle));
+        } else {
+            log::warn!("Tried to focus a component that isn't focusable, ignoring");
+        }
+    }
+
+    /// Removes keyboard focus, emitting `FocusLost` if a component was focused.
+    pub fn blur(&mut self) {
+        self.set_focused(None);
+    }
+
+    /// All focusable components in the order Tab moves through them: components with a tab index
+    /// first (lowest first), followed by the rest in tree order.
+    pub fn focus_order(&self) -> Vec<PreonComponentHandle> {
+        let mut order: Vec<PreonComponentHandle> = self
+            .depth_first(self.root)
+            .filter(|&handle| self.get_component(handle).unwrap().focusable)
+            .collect();
+
+        // Stable, so equal tab indices keep their tree order
+        order.sort_by_key(|&handle| match self.get_component(handle).unwrap().tab_index {
+            Some(tab_index) => (0, tab_index),
+            None => (1, 0),
+        });
+
+        order
+    }
+
+    /// Focuses the next component in [`PreonEngine::focus_order`] (or the previous one if `backwards`
+    /// is set), wrapping around at the end. This is what Tab and Shift+Tab do.
+    pub fn focus_next(&mut self, backwards: bool) {
+        let order = self.focus_order();
+
+        if order.is_empty() {
+            return;
+        }
+
+        let current = self
+            .focused
+            .and_then(|focused| order.iter().position(|&handle| handle == focused));
+
+        let next = match (current, backwards) {
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+            (Some(current), false) => (current + 1) % order.len(),
+            (Some(current), true) => (current + order.len() - 1) % order.len(),
+        };
+
+        self.set_focused(Some(order[next]));
+    }
+
+    fn set_focused(&mut self, handle: Option<PreonComponentHandle>) {
+        if handle == self.focused {
+            return;
+        }
+
+        if let Some(old_focused) = core::mem::replace(&mut self.focused, handle) {
+            let component = self.components.get_mut(old_focused).unwrap();
+            component.focused = false;
+
+            // An unfinished composition is dropped together with the focus
+            if let Some(ref mut state) = component.text_input {
+                state.set_preedit(String::new(), None);
+            }
+
+            self.mark_dirty(old_focused, dirty::PAINT);
+            self.events.push(PreonEvent::FocusLost(old_focused));
+        }
+
+        if let Some(handle) = handle {
+            self.components.get_mut(handle).unwrap().focused = true;
+            self.mark_dirty(handle, dirty::PAINT);
+            self.events.push(PreonEvent::FocusGained(handle));
+        }
+    }
+
+    /// Binds `chord` to `action`, emitting [`PreonEvent::Action`] when it's pressed. Scoped shortcuts
+    /// only work while the focused component is `scope` or one of its descendants, and take
+    /// precedence over shortcuts of enclosing scopes and global shortcuts (`scope` is `None`).
+    /// Scoped shortcuts are removed together with their component. Keys the focused text input
+    /// edits with (Ctrl+C, arrows, ...) never trigger shortcuts.
+    ///
+    /// Fails if `chord` is already bound to a different action in the same scope.
+    ///
+    /// ```ignore
+    /// engine.add_shortcut(PreonKeyChord::new(PreonModifiers::CTRL, PreonKeyCode::S), "save", None)?;
+    /// ```
+    pub fn add_shortcut(
+        &mut self,
+        chord: PreonKeyChord,
+        action: &str,
+        scope: Option<PreonComponentHandle>,
+    ) -> Result<(), PreonShortcutConflict> {
+        if let Some(scope) = scope {
+            assert!(
+                self.components.contains(scope),
+                "Invalid PreonComponentHandle"
+            );
+        }
+
+        match self.shortcuts.entry((chord, scope)) {
+            Entry::Occupied(entry) if entry.get() != action => Err(PreonShortcutConflict {
+                chord,
+                scope,
+                action: entry.get().clone(),
+            }),
+            Entry::Occupied(_) => Ok(()),
+            Entry::Vacant(entry) => {
+                entry.insert(String::from(action));
+                Ok(())
+            }
+        }
+    }
+
+    /// Unbinds `chord` in `scope`, returning the action it was bound to.
+    pub fn remove_shortcut(
+        &mut self,
+        chord: PreonKeyChord,
+        scope: Option<PreonComponentHandle>,
+    ) -> Option<String> {
+        self.shortcuts.remove(&(chord, scope))
+    }
+
+    /// Returns the action `chord` triggers with the current focus, see [`PreonEngine::add_shortcut`].
+    pub fn find_shortcut(&self, chord: PreonKeyChord) -> Option<&str> {
+        let scopes = self
+            .focused
+            .into_iter()
+            .flat_map(|focused| core::iter::once(focused).chain(self.ancestors(focused)))
+            .map(Some)
+            .chain(core::iter::once(None));
+
+        for scope in scopes {
+            if let Some(action) = self.shortcuts.get(&(chord, scope)) {
+                return Some(action);
+            }
+        }
+
+        None
+    }
+
+    /// Calls `listener` for every event dispatched to `handle` or one of its descendants, see
+    /// [`PreonEngine::dispatch`]. Capturing listeners are called on the way down from the root,
+    /// the others on the way back up. Listeners are dropped when their component is removed.
+    ///
+    /// ```ignore
+    /// engine.add_event_listener(card, false, |ctx| {
+    ///     if let PreonComponentEvent::MouseInput(PreonMouseButton::Left, PreonButtonState::Pressed) = ctx.event() {
+    ///         ctx.stop_propagation();
+    ///     }
+    /// });
+    /// ```
+    pub fn add_event_listener<F>(&mut self, handle: PreonComponentHandle, capture: bool, listener: F)
+    where
+        F: FnMut(&mut PreonEventContext) + 'static,
+    {
+        assert!(
+            self.components.contains(handle),
+            "Invalid PreonComponentHandle"
+        );
+
+        self.listeners
+            .entry(handle)
+            .or_default()
+            .push(PreonEventListener {
+                capture,
+                callback: Box::new(listener),
+            });
+    }
+
+    /// Calls `handler` for events of `kind` dispatched to `handle` or one of its descendants,
+    /// after the listeners of those descendants. The handler is dropped when `handle` is removed.
+    ///
+    /// ```ignore
+    /// engine.on(card, PreonEventKind::Pressed, |ctx| {
+    ///     let card = ctx.current_target();
+    ///     ctx.engine.edit_style(card, |style| style.background = PreonBackground::Color(PreonColor::BLACK));
+    /// });
+    /// ```
+    pub fn on<F>(&mut self, handle: PreonComponentHandle, kind: PreonEventKind, mut handler: F)
+    where
+        F: FnMut(&mut PreonEventContext) + 'static,
+    {
+        self.add_event_listener(handle, false, move |ctx| {
+            if kind.matches(ctx.event()) {
+                handler(ctx);
+            }
+        });
+    }
+
+    /// Sends `event` to the listeners of `target` and its ancestors: first capturing from the
+    /// root down, then the target itself, then bubbling back up to the root. Returns true if a
+    /// listener marked the event as handled.
+    ///
+    /// The engine dispatches pointer and keyboard events by itself, this is only needed for
+    /// events created by the user.
+    pub fn dispatch(&mut self, target: PreonComponentHandle, event: PreonComponentEvent) -> bool {
+        // The target might have been removed while a mouse button was held down
+        if !self.components.contains(target) {
+            return false;
+        }
+
+        let mut path: Vec<PreonComponentHandle> = self.ancestors(target).collect();
+        path.reverse();
+
+        let steps: Vec<(PreonComponentHandle, PreonEventPhase)> = path
+            .iter()
+            .map(|&handle| (handle, PreonEventPhase::Capture))
+            .chain(core::iter::once((target, PreonEventPhase::Target)))
+            .chain(path.iter().rev().map(|&handle| (handle, PreonEventPhase::Bubble)))
+            .collect();
+
+        let mut propagation_stopped = false;
+        let mut handled = false;
+
+        for (current, phase) in steps {
+            if propagation_stopped {
+                break;
+            }
+
+            // Taken out while calling them, so listeners can use the engine
+            let mut listeners = match self.listeners.remove(&current) {
+                Some(listeners) => listeners,
+                None => continue,
+            };
+
+            for listener in listeners.iter_mut().filter(|l| l.listens_to(phase)) {
+                let mut context = PreonEventContext {
+                    engine: self,
+                    event: &event,
+                    target,
+                    current_target: current,
+                    phase,
+                    propagation_stopped,
+                    handled,
+                };
+
+                (listener.callback)(&mut context);
+
+                propagation_stopped = context.propagation_stopped;
+                handled = context.handled;
+            }
+
+            // Dropped if a listener removed the component, keeping listeners added in the meantime
+            if self.components.contains(current) {
+                if let Some(added) = self.listeners.remove(&current) {
+                    listeners.extend(added);
+                }
+
+                self.listeners.insert(current, listeners);
+            }
+        }
+
+        handled
+    }
+
+    /// Returns the modifier keys currently held down, tracked from [`PreonUserEvent::KeyboardInput`]
+    /// and [`PreonUserEvent::ModifiersChanged`].
+    pub fn modifiers(&self) -> PreonModifiers {
+        self.modifiers
+    }
+
+    /// Emits [`PreonEvent::Timer`] once, `delay` after the current time. Time only passes with
+    /// [`PreonUserEvent::Tick`], so hosts without a clock can supply their own.
+    pub fn set_timeout(&mut self, delay: Duration) -> PreonTimerId {
+        self.add_timer(delay, None)
+    }
+
+    /// Emits [`PreonEvent::Timer`] every `interval`, until it's removed with
+    /// [`PreonEngine::clear_timer`]. Missed intervals (after a long tick) are skipped, and a zero
+    /// interval fires on every update with a tick.
+    pub fn set_interval(&mut self, interval: Duration) -> PreonTimerId {
+        self.add_timer(interval, Some(interval))
+    }
+
+    /// Stops a timeout or interval. Returns false if it already fired, or was cleared before.
+    pub fn clear_timer(&mut self, id: PreonTimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+
+        self.timers.len() != len
+    }
+
+    /// How long until the next timer fires, counted from the last [`PreonUserEvent::Tick`].
+    /// Render modules can sleep this long when there is nothing else to do, see
+    /// [`PreonEngine::needs_update`]. `None` if there are no timers.
+    pub fn next_timer_due(&self) -> Option<Duration> {
+        self.timers
+            .iter()
+            .map(|timer| timer.due.saturating_sub(self.time))
+            .min()
+    }
+
+    /// Plays `keyframes` on `handle`, replacing any running animation of the same property.
+    /// The property returns to its value in the style once the animation is over, use
+    /// [`PreonKeyframes::forever`] to keep it going.
+    ///
+    /// ```ignore
+    /// engine.animate(
+    ///     spinner,
+    ///     PreonKeyframes::new(PreonAnimatable::Opacity, Duration::from_millis(800))
+    ///         .frame(0.5, PreonAnimationValue::Number(0.3), PreonEasing::EASE_IN_OUT)
+    ///         .forever()
+    ///         .alternate(),
+    /// );
+    /// ```
+    pub fn animate(&mut self, handle: PreonComponentHandle, keyframes: PreonKeyframes) {
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
+        let property = keyframes.property;
+        let target = component.animation_target(property);
+        let state = component.animation.get_or_insert_with(Box::default);
+
+        let animation = PreonAnimation::Keyframes {
+            keyframes,
+            start: self.time,
+        };
+
+        match state.values.iter_mut().find(|value| value.property == property) {
+            Some(value) => value.animation = Some(animation),
+            None => state.values.push(PreonAnimatedValue {
+                property,
+                target,
+                current: target,
+                animation: Some(animation),
+            }),
+        }
+
+        if !self.animated.contains(&handle) {
+            self.animated.push(handle);
+        }
+
+        self.mark_dirty(handle, dirty::PAINT);
+    }
+
+    /// Stops the transition or animation of `property` on `handle`, jumping to its value in the
+    /// style. Returns false if it wasn't animating.
+    pub fn stop_animation(&mut self, handle: PreonComponentHandle, property: PreonAnimatable) -> bool {
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
+
+        let value = component.animation.as_mut().and_then(|state| {
+            state
+                .values
+                .iter_mut()
+                .find(|value| value.property == property && value.animation.is_some())
+        });
+
+        match value {
+            Some(value) => {
+                value.animation = None;
+                self.mark_animated_dirty(handle, property);
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scrolls the scroll view `handle` so `offset` (relative to the top left corner of its
+    /// content) is shown at the top left corner. The offset is clamped to
+    /// [`PreonComponent::max_scroll_offset`], and any inertial scrolling stops.
+    pub fn scroll_to(&mut self, handle: PreonComponentHandle, offset: PreonVector<i32>) {
+        if !self.is_scroll_view(handle) {
+            return;
+        }
+
+        self.stop_inertia(handle);
+        self.set_scroll_offset(handle, PreonVector::new(offset.x as f32, offset.y as f32));
+    }
+
+    /// Scrolls the scroll view `handle` by `delta`, see [`PreonEngine::scroll_to`]. Returns true
+    /// if the content moved.
+    pub fn scroll_by(&mut self, handle: PreonComponentHandle, delta: PreonVector<i32>) -> bool {
+        if !self.is_scroll_view(handle) {
+            return false;
+        }
+
+        self.stop_inertia(handle);
+
+        let offset = self.get_component(handle).unwrap().scroll.as_ref().unwrap().offset;
+        self.set_scroll_offset(
+            handle,
+            offset + PreonVector::new(delta.x as f32, delta.y as f32),
+        )
+    }
+
+    fn is_scroll_view(&self, handle: PreonComponentHandle) -> bool {
+        let is_scroll_view = self
+            .get_component(handle)
+            .expect("Invalid PreonComponentHandle")
+            .is_scroll_view();
+
+        if !is_scroll_view {
+            log::warn!("Only scroll views can be scrolled");
+        }
+
+        is_scroll_view
+    }
+
+    /// The scroll views containing `handle` (including `handle` itself), innermost first.
+    fn scroll_views(&self, handle: PreonComponentHandle) -> Vec<PreonComponentHandle> {
+        core::iter::once(handle)
+            .chain(self.ancestors(handle))
+            .filter(|&ancestor| self.get_component(ancestor).unwrap().is_scroll_view())
+            .collect()
+    }
+
+    /// Moves the content of scroll view `handle`, returns true if the (clamped) offset changed.
+    fn set_scroll_offset(&mut self, handle: PreonComponentHandle, offset: PreonVector<f32>) -> bool {
+        let component = self.get_component_mut(handle).unwrap();
+        let offset = component.clamp_scroll_offset(offset);
+        let scroll = component.scroll.as_mut().unwrap();
+
+        if scroll.offset == offset {
+            return false;
+        }
+
+        scroll.offset = offset;
+
+        let applied_offset = PreonVector::new(libm::roundf(offset.x) as i32, libm::roundf(offset.y) as i32);
+        let delta = scroll.applied_offset - applied_offset;
+        scroll.applied_offset = applied_offset;
+
+        if delta == PreonVector::zero() {
+            return true;
+        }
+
+        // Positions are absolute, so the whole subtree moves
+        let descendants: Vec<PreonComponentHandle> = self.descendants(handle).collect();
+        for descendant in descendants {
+            let component = self.get_component_mut(descendant).unwrap();
+            component.inner_position = component.inner_position + delta;
+            self.mark_dirty(descendant, dirty::PAINT);
+        }
+
+        // The scrollbars moved
+        self.mark_dirty(handle, dirty::PAINT);
+        self.hover_outdated = true;
+
+        true
+    }
+
+    fn stop_inertia(&mut self, handle: PreonComponentHandle) {
+        self.scrolling.retain(|&scrolling| scrolling != handle);
+
+        if let Some(scroll) = self.get_component_mut(handle).unwrap().scroll.as_mut() {
+            scroll.velocity = PreonVector::zero();
+        }
+    }
+
+    /// Scrolls the closest scroll view under the mouse that can still move in the direction of
+    /// `event`, returns false if there is none.
+    fn scroll_wheel(&mut self, event: PreonScrollEvent) -> bool {
+        // A listener might have removed the target
+        if !self.components.contains(event.target) {
+            return false;
+        }
+
+        let mut delta = event.delta.to_pixels(SCROLL_LINE_HEIGHT);
+
+        // Shift turns vertical scrolling sideways
+        if event.modifiers.shift() && delta.x == 0.0 {
+            delta = PreonVector::new(delta.y, 0.0);
+        }
+
+        // Text areas scroll their own text before the scroll views around them
+        let component = self.get_component_mut(event.target).unwrap();
+        if let Some(ref mut state) = component.text_input {
+            let max_scroll = (state.text_height() - state.view_height).max(0.0);
+            let scroll = (state.scroll.y - delta.y).min(max_scroll).max(0.0);
+
+            if state.multiline && !state.read_only && scroll != state.scroll.y {
+                state.scroll.y = scroll;
+                self.mark_dirty(event.target, dirty::PAINT);
+                return true;
+            }
+        }
+
+        for view in self.scroll_views(event.target) {
+            let component = self.get_component_mut(view).unwrap();
+            let offset = component.scroll.as_ref().unwrap().offset;
+
+            if component.clamp_scroll_offset(offset - delta) == offset {
+                continue;
+            }
+
+            let scroll = component.scroll.as_mut().unwrap();
+
+            // Trackpads already send smoothed deltas
+            if scroll.inertia && matches!(event.delta, PreonScrollDelta::Lines(_)) {
+                // Slowing down from this velocity covers exactly `delta`
+                scroll.velocity = scroll.velocity - delta * INERTIA_FRICTION;
+
+                if !self.scrolling.contains(&view) {
+                    self.scrolling.push(view);
+                }
+            } else {
+                self.set_scroll_offset(view, offset - delta);
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Moves the scroll views with inertia, `delta` being the time since the last tick.
+    fn update_inertia(&mut self, delta: Duration) {
+        let seconds = delta.min(MAX_INERTIA_STEP).as_secs_f32();
+        let decay = libm::expf(-INERTIA_FRICTION * seconds);
+
+        for handle in core::mem::take(&mut self.scrolling) {
+            let component = self.get_component_mut(handle).unwrap();
+            let scroll = component.scroll.as_ref().unwrap();
+
+            let target = scroll.offset + scroll.velocity * ((1.0 - decay) / INERTIA_FRICTION);
+            let mut velocity = scroll.velocity * decay;
+
+            // Stop at the edges
+            let clamped = component.clamp_scroll_offset(target);
+            if clamped.x != target.x {
+                velocity.x = 0.0;
+            }
+            if clamped.y != target.y {
+                velocity.y = 0.0;
+            }
+
+            if velocity.length32() < MIN_INERTIA_VELOCITY {
+                velocity = PreonVector::zero();
+            }
+
+            component.scroll.as_mut().unwrap().velocity = velocity;
+
+            if velocity != PreonVector::zero() {
+                self.scrolling.push(handle);
+            }
+
+            self.set_scroll_offset(handle, target);
+        }
+    }
+
+    /// Scrolls the closest scroll view containing the focused component (or the mouse, if nothing
+    /// has focus) with PageUp, PageDown, Home and End.
+    fn scroll_with_key(&mut self, key: PreonKeyCode) {
+        let target = match self.focused {
+            Some(focused) => Some(focused),
+            None if self.mouse_in_window => self.component_at(self.mouse_position),
+            None => None,
+        };
+
+        let view = match target.and_then(|target| self.scroll_views(target).first().copied()) {
+            Some(view) => view,
+            None => return,
+        };
+
+        let component = self.get_component(view).unwrap();
+        let offset = component.scroll.as_ref().unwrap().offset;
+        // Keep a line of the previous page visible
+        let page = (component.get_content_size().y as f32 - SCROLL_LINE_HEIGHT).max(SCROLL_LINE_HEIGHT);
+
+        let offset = match key {
+            PreonKeyCode::PageUp => PreonVector::new(offset.x, offset.y - page),
+            PreonKeyCode::PageDown => PreonVector::new(offset.x, offset.y + page),
+            PreonKeyCode::Home => PreonVector::new(offset.x, 0.0),
+            PreonKeyCode::End => PreonVector::new(offset.x, component.max_scroll_offset().y as f32),
+            _ => return,
+        };
+
+        self.stop_inertia(view);
+        self.set_scroll_offset(view, offset);
+    }
+
+    /// Starts dragging the scrollbar under the mouse, returns false if there is none.
+    fn start_scrollbar_drag(&mut self) -> bool {
+        let point = self.mouse_position;
+        let target = match self.component_at(point) {
+            Some(target) => target,
+            None => return false,
+        };
+
+        for view in self.scroll_views(target) {
+            let component = self.get_component(view).unwrap();
+
+            for axis in [PreonScrollAxis::Horizontal, PreonScrollAxis::Vertical] {
+                let thumb = match (component.scrollbar_track(axis), component.scrollbar_thumb(axis)) {
+                    (Some(track), Some(thumb)) if track.contains_point(point) => thumb,
+                    _ => continue,
+                };
+
+                let on_thumb = thumb.contains_point(point);
+                let grab = if on_thumb {
+                    axis.along(point - thumb.position)
+                } else {
+                    axis.along(thumb.size) / 2
+                };
+
+                self.scrollbar_drag = Some(PreonScrollbarDrag {
+                    handle: view,
+                    axis,
+                    grab,
+                });
+
+                // Pressing next to the thumb centers it on the mouse
+                if !on_thumb {
+                    self.drag_scrollbar();
+                }
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn drag_scrollbar(&mut self) {
+        let drag = match self.scrollbar_drag {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        let component = match self.get_component(drag.handle) {
+            Some(component) => component,
+            None => {
+                self.scrollbar_drag = None;
+                return;
+            }
+        };
+
+        let track = match component.scrollbar_track(drag.axis) {
+            Some(track) => track,
+            None => return,
+        };
+
+        let thumb_offset = drag.axis.along(self.mouse_position - track.position) - drag.grab;
+        let value = component.thumb_to_scroll_offset(drag.axis, thumb_offset);
+        let mut offset = component.scroll.as_ref().unwrap().offset;
+
+        match drag.axis {
+            PreonScrollAxis::Horizontal => offset.x = value,
+            PreonScrollAxis::Vertical => offset.y = value,
+        }
+
+        self.stop_inertia(drag.handle);
+        self.set_scroll_offset(drag.handle, offset);
+    }
+
+    fn mouse_input(&mut self, button: PreonMouseButton, state: PreonButtonState) {
+        // Scrollbars are drawn on top of the content, so they get the mouse first
+        if let PreonMouseButton::Left = button {
+            match state {
+                PreonButtonState::Pressed if self.start_scrollbar_drag() => return,
+                PreonButtonState::Released if self.scrollbar_drag.take().is_some() => return,
+                _ => (),
+            }
+        }
+
+        let target = match state {
+            PreonButtonState::Pressed => {
+                let target = self.hit_test(self.mouse_position);
+                self.pressed.retain(|&(pressed, _)| pressed != button);
+
+                if let Some(target) = target {
+                    self.pressed.push((button, target));
+                }
+
+                target
+            }
+            // Released goes to the pressed component, even if the mouse left it
+            PreonButtonState::Released => self
+                .pressed
+                .iter()
+                .position(|&(pressed, _)| pressed == button)
+                .map(|index| self.pressed.remove(index).1),
+        };
+
+        let click_count = match state {
+            PreonButtonState::Pressed => self.count_click(target, button),
+            PreonButtonState::Released => match self.last_click {
+                Some(click) if click.button == button => click.count,
+                _ => 1,
+            },
+        };
+
+        // The pressed component might have been removed in the meantime
+        let target = target.filter(|&target| self.components.contains(target));
+
+        if let Some(target) = target {
+            let event = PreonMouseEvent {
+                target,
+                button,
+                state,
+                position: self.mouse_position,
+                local_position: self.mouse_position
+                    - self.get_component(target).unwrap().get_border_position(),
+                click_count,
+                modifiers: self.modifiers,
+            };
+
+            if self.dispatch(target, PreonComponentEvent::MouseInput(event)) {
+                return;
+            }
+
+            self.events.push(PreonEvent::ComponentMouseInput(event));
+        }
+
+        if let PreonMouseButton::Left = button {
+            if let PreonButtonState::Pressed = state {
+                // Pressing outside of any focusable component removes focus. Selectable labels are
+                // focused by clicking them, without being in the focus order.
+                let focus = target.and_then(|target| {
+                    core::iter::once(target)
+                        .chain(self.ancestors(target))
+                        .find(|&handle| {
+                            let component = self.get_component(handle).unwrap();
+                            component.focusable || component.is_selectable()
+                        })
+                });
+                self.set_focused(focus);
+
+                match target {
+                    Some(target) if self.get_component(target).unwrap().is_selectable() => {
+                        self.press_text_input(target, click_count)
+                    }
+                    _ => (),
+                }
+            }
+
+            if let Some(target) = target {
+                self.push_component_pressed(target, state);
+            }
+        }
+    }
+
+    fn keyboard_input(&mut self, event: PreonKeyEvent) {
+        self.events.push(PreonEvent::KeyboardInput(event));
+
+        let target = self.focused.unwrap_or(self.root);
+        if self.dispatch(target, PreonComponentEvent::KeyboardInput(event)) {
+            return;
+        }
+
+        // The focused text input gets its editing keys before shortcuts do, so a global Ctrl+C
+        // doesn't break copying from it
+        if let (Some(focused), PreonButtonState::Pressed) = (self.focused, event.state) {
+            if event.key != PreonKeyCode::Tab && self.text_input_key(focused, event) {
+                self.events
+                    .push(PreonEvent::ComponentKeyboardInput(focused, event));
+                return;
+            }
+        }
+
+        if matches!(event.state, PreonButtonState::Pressed) && !event.is_repeat {
+            let chord = PreonKeyChord::new(event.modifiers, event.key);
+
+            if let Some(action) = self.find_shortcut(chord) {
+                let action = String::from(action);
+                self.events.push(PreonEvent::Action(action));
+                return;
+            }
+        }
+
+        if event.key == PreonKeyCode::Tab {
+            if let PreonButtonState::Pressed = event.state {
+                self.focus_next(event.modifiers.shift());
+            }
+        } else if let Some(focused) = self.focused {
+            self.events
+                .push(PreonEvent::ComponentKeyboardInput(focused, event));
+
+            // Focused components receiving mouse events (buttons) can be pressed with Space and Return
+            if let PreonKeyCode::Space | PreonKeyCode::Return = event.key {
+                let component = self.get_component(focused).unwrap();
+
+                if !event.is_repeat && component.mouse_events && !component.is_selectable() {
+                    self.push_component_pressed(focused, event.state);
+                }
+            }
+        }
+
+        if let PreonButtonState::Pressed = event.state {
+            self.scroll_with_key(event.key);
+        }
+    }
+
+    /// Edits the text input `handle` with an editing key, returns false for other keys (and other
+    /// components).
+    fn text_input_key(&mut self, handle: PreonComponentHandle, event: PreonKeyEvent) -> bool {
+        let component = self.get_component(handle).unwrap();
+        if !component.is_selectable() {
+            return false;
+        }
+
+        // Input methods handle the keys while composing
+        if component.text_input().unwrap().is_composing() {
+            return true;
+        }
+
+        // Moving between lines needs to know where they are right now
+        self.measure_text_input(handle);
+
+        let component = self.components.get_mut(handle).unwrap();
+        let state = component.text_input.as_mut().unwrap();
+        let text = &mut component.text;
+
+        let ctrl = event.modifiers.ctrl();
+        let extend = event.modifiers.shift();
+        let (left, right) = if ctrl {
+            (PreonCaretMotion::WordLeft, PreonCaretMotion::WordRight)
+        } else {
+            (PreonCaretMotion::Left, PreonCaretMotion::Right)
+        };
+        let (home, end) = if ctrl {
+            (PreonCaretMotion::Home, PreonCaretMotion::End)
+        } else {
+            (PreonCaretMotion::LineStart, PreonCaretMotion::LineEnd)
+        };
+        let old_selection = (state.anchor, state.caret);
+
+        let motion = match event.key {
+            PreonKeyCode::Left => Some(left),
+            PreonKeyCode::Right => Some(right),
+            PreonKeyCode::Home => Some(home),
+            PreonKeyCode::End => Some(end),
+            // Single lines leave these to scroll views
+            PreonKeyCode::Up if state.multiline => Some(PreonCaretMotion::Up),
+            PreonKeyCode::Down if state.multiline => Some(PreonCaretMotion::Down),
+            PreonKeyCode::PageUp if state.multiline => Some(PreonCaretMotion::PageUp),
+            PreonKeyCode::PageDown if state.multiline => Some(PreonCaretMotion::PageDown),
+            _ => None,
+        };
+
+        let changed = if let Some(motion) = motion {
+            state.move_caret(text, motion, extend);
+            false
+        } else {
+            match event.key {
+                PreonKeyCode::Backspace => state.delete(text, left),
+                PreonKeyCode::Delete => state.delete(text, right),
+                PreonKeyCode::A if ctrl => {
+                    state.select(text, 0, text.len());
+                    false
+                }
+                PreonKeyCode::Z if ctrl && extend => state.redo(text),
+                PreonKeyCode::Z if ctrl => state.undo(text),
+                PreonKeyCode::Y if ctrl => state.redo(text),
+                PreonKeyCode::C if ctrl => return self.copy_text(handle, false),
+                PreonKeyCode::Copy => return self.copy_text(handle, false),
+                PreonKeyCode::X if ctrl => return self.copy_text(handle, true),
+                PreonKeyCode::Cut => return self.copy_text(handle, true),
+                PreonKeyCode::V if ctrl => return self.paste_text(handle),
+                PreonKeyCode::Paste => return self.paste_text(handle),
+                // Labels don't take Return
+                PreonKeyCode::Return | PreonKeyCode::NumpadEnter if state.read_only => return false,
+                // Text areas take line breaks, and are submitted with Ctrl+Return
+                PreonKeyCode::Return | PreonKeyCode::NumpadEnter if state.multiline && !ctrl => {
+                    state.insert(text, "\n", false)
+                }
+                PreonKeyCode::Return | PreonKeyCode::NumpadEnter => {
+                    self.events.push(PreonEvent::Submitted(handle));
+                    return true;
+                }
+                _ => return false,
+            }
+        };
+
+        let moved = old_selection != (state.anchor, state.caret);
+
+        if changed {
+            self.events.push(PreonEvent::TextChanged(handle));
+        }
+
+        if changed || moved {
+            self.mark_dirty(handle, dirty::PAINT);
+        }
+
+        true
+    }
+
+    /// Replaces the selection of the text input `handle` with `insert`, returns false if nothing
+    /// changed (or if it isn't a text input).
+    fn insert_text(&mut self, handle: PreonComponentHandle, insert: &str) -> bool {
+        let component = self.components.get_mut(handle).unwrap();
+
+        // Typing a word is undone at once
+        let changed = match component.text_input {
+            Some(ref mut state) => state.insert(&mut component.text, insert, true),
+            None => false,
+        };
+
+        if changed {
+            self.events.push(PreonEvent::TextChanged(handle));
+            self.mark_dirty(handle, dirty::PAINT);
+        }
+
+        changed
+    }
+
+    /// Copies the selection of the text input (or selectable label) `handle` to the clipboard,
+    /// deleting it from a text input with `cut` set. Passwords are never copied.
+    fn copy_text(&mut self, handle: PreonComponentHandle, cut: bool) -> bool {
+        let component = self.components.get_mut(handle).unwrap();
+        let state = component.text_input.as_mut().unwrap();
+
+        if state.copy(&mut component.text, &mut *self.clipboard, cut) {
+            self.events.push(PreonEvent::TextChanged(handle));
+            self.mark_dirty(handle, dirty::PAINT);
+        }
+
+        true
+    }
+
+    /// Replaces the selection of the text input `handle` with the text on the clipboard. Line breaks
+    /// become spaces in single line text inputs.
+    fn paste_text(&mut self, handle: PreonComponentHandle) -> bool {
+        let component = self.components.get_mut(handle).unwrap();
+        let state = component.text_input.as_mut().unwrap();
+
+        if state.paste(&mut component.text, &mut *self.clipboard) {
+            self.events.push(PreonEvent::TextChanged(handle));
+            self.mark_dirty(handle, dirty::PAINT);
+        }
+
+        true
+    }
+
+    /// Shows the composition of an input method in the focused text input, see
+    /// [`PreonUserEvent::ImePreedit`].
+    fn ime_preedit(&mut self, preedit: String, cursor: Option<Range<usize>>) {
+        let handle = match self.focused {
+            Some(focused) if self.get_component(focused).unwrap().is_text_input() => focused,
+            _ => return,
+        };
+
+        let component = self.components.get_mut(handle).unwrap();
+        let state = component.text_input.as_mut().unwrap();
+
+        // Starting a composition replaces the selection, like typing does
+        if !state.is_composing() && !preedit.is_empty() && state.insert(&mut component.text, "", false) {
+            self.events.push(PreonEvent::TextChanged(handle));
+        }
+
+        state.set_preedit(preedit, cursor);
+        self.mark_dirty(handle, dirty::PAINT);
+    }
+
+    /// Ends the composition of the focused text input, inserting `text`.
+    fn ime_commit(&mut self, text: &str) {
+        let handle = match self.focused {
+            Some(focused) if self.get_component(focused).unwrap().is_text_input() => focused,
+            _ => return,
+        };
+
+        let state = self.components.get_mut(handle).unwrap().text_input.as_mut().unwrap();
+        state.set_preedit(String::new(), None);
+
+        if !self.insert_text(handle, text) {
+            self.mark_dirty(handle, dirty::PAINT);
+        }
+    }
+
+    /// Sends [`PreonEvent::ImeCursorArea`] if the caret of the focused text input moved, or focus
+    /// moved to or away from a text input.
+    fn update_ime_cursor_area(&mut self) {
+        let area = match self.focused {
+            Some(focused) => {
+                let component = self.get_component(focused).unwrap();
+
+                // Passwords aren't typed with input methods
+                match component.text_input() {
+                    Some(state) if component.is_text_input() && !state.password => component.caret_rect(),
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+
+        if area != self.ime_cursor_area {
+            self.ime_cursor_area = area;
+            self.events.push(PreonEvent::ImeCursorArea(area));
+        }
+    }
+
+    /// Places the caret of the text input `handle` under the mouse (extending the selection with
+    /// Shift), double clicks select a word and triple clicks everything.
+    fn press_text_input(&mut self, handle: PreonComponentHandle, click_count: u32) {
+        // Clicking ends the composition, the input method starts a new one at the new caret
+        if let Some(ref mut state) = self.components.get_mut(handle).unwrap().text_input {
+            state.set_preedit(String::new(), None);
+        }
+
+        self.measure_text_input(handle);
+
+        let extend = self.modifiers.shift();
+        let component = self.components.get_mut(handle).unwrap();
+        let position = self.mouse_position - component.text_origin();
+        let state = component.text_input.as_mut().unwrap();
+        let text = &component.text;
+
+        let index = state.index_at(PreonVector::new(position.x as f32, position.y as f32));
+
+        match click_count {
+            1 => state.set_caret(index, extend),
+            2 => {
+                let word = text_input::word_at(text, index);
+                state.select(text, word.start, word.end);
+            }
+            _ => state.select(text, 0, text.len()),
+        }
+
+        self.mark_dirty(handle, dirty::PAINT);
+    }
+
+    /// Extends the selection of a text input while the left mouse button is held down on it.
+    fn drag_text_selection(&mut self) {
+        let handle = match self
+            .pressed
+            .iter()
+            .find(|&&(button, _)| button == PreonMouseButton::Left)
+        {
+            Some(&(_, handle)) => handle,
+            None => return,
+        };
+
+        let component = match self.components.get_mut(handle) {
+            Some(component) if component.is_selectable() => component,
+            _ => return,
+        };
+
+        // Measured during the last paint, the text might have changed since
+        let position = self.mouse_position - component.text_origin();
+        let state = component.text_input.as_mut().unwrap();
+        let caret = state.index_at(PreonVector::new(position.x as f32, position.y as f32));
+        let caret = text_input::clamp_index(&component.text, caret);
+
+        if caret != state.caret {
+            state.set_caret(caret, true);
+            self.mark_dirty(handle, dirty::PAINT);
+        }
+    }
+
+    /// Remembers a press of `button` on `target`, returning how many times in a row it has been clicked.
+    fn count_click(&mut self, target: Option<PreonComponentHandle>, button: PreonMouseButton) -> u32 {
+        let count = match (target, self.last_click) {
+            (Some(target), Some(click))
+                if click.target == target
+                    && click.button == button
+                    && self.time - click.time <= DOUBLE_CLICK_TIME
+                    && (self.mouse_position.x - click.position.x).abs() <= DOUBLE_CLICK_DISTANCE
+                    && (self.mouse_position.y - click.position.y).abs() <= DOUBLE_CLICK_DISTANCE =>
+            {
+                click.count + 1
+            }
+            _ => 1,
+        };
+
+        self.last_click = target.map(|target| PreonClick {
+            target,
+            button,
+            position: self.mouse_position,
+            time: self.time,
+            count,
+        });
+
+        count
+    }
+
+    fn push_component_pressed(&mut self, handle: PreonComponentHandle, state: PreonButtonState) {
+        if let Some(component) = self.get_component(handle) {
+            let id = String::from(component.id().unwrap_or_default());
+            self.events.push(PreonEvent::ComponentPressed(id, state));
+        }
+    }
+
+    fn move_next_to(
+        &mut self,
+        handle: PreonComponentHandle,
+        sibling: PreonComponentHandle,
+        offset: usize,
+    ) {
+        if handle == sibling {
+            return;
+        }
+
+        let parent = self
+            .get_component(sibling)
+            .expect("Invalid PreonComponentHandle")
+            .parent
+            .expect("The root component has no siblings");
+
+        // `reparent` detaches `handle` first, which moves `sibling` back by one if it came after it
+        let children = &self.get_component(parent).unwrap().children;
+        let mut index = children.iter().position(|&child| child == sibling).unwrap() + offset;
+
+        if let Some(current) = children.iter().position(|&child| child == handle) {
+            if current < index {
+                index -= 1;
+            }
+        }
+
+        self.reparent(handle, parent, index);
+    }
+
+    /// Removes `handle` from the children of its parent, without removing the component itself.
+    fn detach(&mut self, handle: PreonComponentHandle) {
+        let parent = self
+            .get_component_mut(handle)
+            .expect("Invalid PreonComponentHandle")
+            .parent
+            .take();
+
+        if let Some(parent) = parent {
+            if let Some(parent) = self.get_component_mut(parent) {
+                parent.children.retain(|&child| child != handle);
+            }
+        }
+    }
+
+    fn add_timer(&mut self, delay: Duration, interval: Option<Duration>) -> PreonTimerId {
+        let id = PreonTimerId(self.next_timer_id);
+        self.next_timer_id = self.next_timer_id.wrapping_add(1);
+
+        self.timers.push(PreonTimer {
+            id,
+            due: self.time + delay,
+            interval,
+        });
+
+        id
+    }
+
+    /// Emits [`PreonEvent::Timer`] for every timer that is due, earliest first.
+    fn fire_timers(&mut self) {
+        let mut due: Vec<(Duration, PreonTimerId)> = self
+            .timers
+            .iter()
+            .filter(|timer| timer.due <= self.time)
+            .map(|timer| (timer.due, timer.id))
+            .collect();
+
+        if due.is_empty() {
+            return;
+        }
+
+        due.sort();
+        for &(_, id) in due.iter() {
+            self.events.push(PreonEvent::Timer(id));
+        }
+
+        let time = self.time;
+        self.timers.retain_mut(|timer| {
+            if timer.due > time {
+                return true;
+            }
+
+            match timer.interval {
+                Some(interval) => {
+                    // Skip the intervals that have been missed
+                    timer.due += interval;
+                    if timer.due <= time {
+                        timer.due = time + interval;
+                    }
+
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// Makes sure `handle` keeps track of the properties in `transitions`, so they can be animated
+    /// once they change.
+    fn track_transitions(&mut self, handle: PreonComponentHandle, transitions: &[PreonTransition]) {
+        let component = self
+            .components
+            .get_mut(handle)
+            .expect("Invalid PreonComponentHandle");
+
+        if transitions.is_empty() {
+            return;
+        }
+
+        let mut state = component.animation.take().unwrap_or_default();
+        for transition in transitions.iter() {
+            if !state.values.iter().any(|value| value.property == transition.property) {
+                let target = component.animation_target(transition.property);
+
+                state.values.push(PreonAnimatedValue {
+                    property: transition.property,
+                    target,
+                    current: target,
+                    animation: None,
+                });
+            }
+        }
+
+        component.animation = Some(state);
+
+        if !self.animated.contains(&handle) {
+            self.animated.push(handle);
+        }
+    }
+
+    /// Starts transitions for properties whose value in the style changed, and applies the
+    /// current value of every running animation.
+    fn update_animations(&mut self) {
+        let time = self.time;
+        let mut index = 0;
+
+        while index < self.animated.len() {
+            let handle = self.animated[index];
+            let component = self.components.get_mut(handle).unwrap();
+            let mut state = component.animation.take().unwrap_or_default();
+            let mut changed: Vec<PreonAnimatable> = Vec::new();
+
+            for value in state.values.iter_mut() {
+                let target = component.animation_target(value.property);
+                let transition = component
+                    .style
+                    .transitions
+                    .iter()
+                    .find(|transition| transition.property == value.property);
+
+                if target != value.target {
+                    // Keyframe animations keep playing, the transition starts from where it is
+                    let from = match value.animation {
+                        Some(PreonAnimation::Keyframes { .. }) => None,
+                        Some(PreonAnimation::Transition { .. }) => Some(value.current),
+                        None => Some(value.target),
+                    };
+
+                    match (from, transition) {
+                        (Some(from), Some(&transition)) => {
+                            value.current = from;
+                            value.animation = Some(PreonAnimation::Transition {
+                                from,
+                                transition,
+                                start: time,
+                            });
+                        }
+                        (Some(_), None) => value.animation = None,
+                        _ => (),
+                    }
+
+                    value.target = target;
+                }
+
+                let sample = match value.animation {
+                    Some(ref animation) => animation.sample(time, &value.target),
+                    None => continue,
+                };
+
+                match sample {
+                    Some(current) if current == value.current => (),
+                    Some(current) => {
+                        value.current = current;
+                        changed.push(value.property);
+                    }
+                    None => {
+                        value.animation = None;
+                        changed.push(value.property);
+                    }
+                }
+            }
+
+            // Values without a transition are only kept around while they animate
+            let transitions = &component.style.transitions;
+            state.values.retain(|value| {
+                value.animation.is_some()
+                    || transitions
+                        .iter()
+                        .any(|transition| transition.property == value.property)
+            });
+
+            if state.values.is_empty() {
+                self.animated.swap_remove(index);
+            } else {
+                component.animation = Some(state);
+                index += 1;
+            }
+
+            for property in changed {
+                self.mark_animated_dirty(handle, property);
+            }
+        }
+    }
+
+    /// Marks `handle` dirty after the value of `property` changed.
+    fn mark_animated_dirty(&mut self, handle: PreonComponentHandle, property: PreonAnimatable) {
+        match property {
+            PreonAnimatable::MinSize | PreonAnimatable::Margin | PreonAnimatable::Padding => {
+                self.mark_dirty(handle, dirty::LAYOUT)
+            }
+            // Descendants are faded as well
+            PreonAnimatable::Opacity => self.mark_subtree_dirty(handle, dirty::PAINT),
+            _ => self.mark_dirty(handle, dirty::PAINT),
+        }
+    }
+
+    fn mark_subtree_dirty(&mut self, handle: PreonComponentHandle, flags: u8) {
+        let subtree: Vec<PreonComponentHandle> = self.depth_first(handle).collect();
+        for current in subtree {
+            self.mark_dirty(current, flags);
+        }
+    }
+
+    /// Combined opacity of `handle` and its ancestors.
+    fn inherited_opacity(&self, handle: PreonComponentHandle) -> f32 {
+        self.ancestors(handle)
+            .fold(self.get_component(handle).unwrap().get_opacity(), |opacity, ancestor| {
+                opacity * self.get_component(ancestor).unwrap().get_opacity()
+            })
+    }
+
+    /// Lays out `handle` if it's dirty (see [`dirty`]), or if `force` is set.
+    fn layout_component(&mut self, handle: PreonComponentHandle, force: bool) {
+        let component = self.get_component(handle).unwrap();
+
+        if !force && component.dirty & (dirty::LAYOUT | dirty::CHILDREN) == 0 {
+            return;
+        }
+
+        self.measure_component(handle, force);
+        self.arrange_component(handle, force);
+    }
+
+    /// First layout pass, from the leaves up: grows components to fit their children. Leaves the
+    /// dirty flags alone, [`PreonEngine::arrange_component`] still needs them.
+    fn measure_component(&mut self, handle: PreonComponentHandle, force: bool) {
+        let component = self.get_component_mut(handle).unwrap();
+
+        if !force && component.dirty & (dirty::LAYOUT | dirty::CHILDREN) == 0 {
+            return;
+        }
+
+        let force = force || component.dirty & dirty::LAYOUT != 0;
+
+        // Components scrolling because of their style get a scroll state on their first layout
+        let scrolls = component.style.overflow_x == PreonOverflow::Scroll
+            || component.style.overflow_y == PreonOverflow::Scroll;
+        if scrolls && component.scroll.is_none() {
+            component.scroll = Some(Box::default());
+        }
+
+        // Same for the properties it has transitions for
+        if !component.style.transitions.is_empty() && component.animation.is_none() {
+            let transitions = component.style.transitions.clone();
+            self.track_transitions(handle, &transitions);
+        }
+
+        let is_root = handle == self.root;
+        let component = self.get_component_mut(handle).unwrap();
+
+        // Sizes are kept between layouts, so fitting components start over from their minimum size.
+        // The root always has the size of the window.
+        if !is_root {
+            reset_fit(component);
+        }
+
+        let children = core::mem::take(&mut component.children);
+        let layout = component.style.layout;
+
+        for &child in children.iter() {
+            self.measure_component(child, force);
+        }
+
+        match layout {
+            PreonLayout::Rows => PreonRowsLayoutProvider::measure(self, handle, &children),
+            PreonLayout::Columns => PreonColumnsLayoutProvider::measure(self, handle, &children),
+            PreonLayout::Container => PreonContainerLayoutProvider::measure(self, handle, &children),
+        }
+
+        self.get_component_mut(handle).unwrap().children = children;
+    }
+
+    /// Second layout pass, from the root down: positions the children inside of their parent,
+    /// whose rect is final by now.
+    fn arrange_component(&mut self, handle: PreonComponentHandle, force: bool) {
+        let component = self.get_component_mut(handle).unwrap();
+
+        let force = force || component.dirty & dirty::LAYOUT != 0;
+        component.dirty &= !(dirty::LAYOUT | dirty::CHILDREN);
+
+        let children = core::mem::take(&mut component.children);
+        let layout = component.style.layout;
+
+        let old_rects: Vec<PreonRect<i32>> = children
+            .iter()
+            .map(|&child| self.get_component(child).unwrap().get_outer_rect())
+            .collect();
+
+        match layout {
+            PreonLayout::Rows => PreonRowsLayoutProvider::arrange(self, handle, &children),
+            PreonLayout::Columns => PreonColumnsLayoutProvider::arrange(self, handle, &children),
+            PreonLayout::Container => PreonContainerLayoutProvider::arrange(self, handle, &children),
+        }
+
+        self.update_overflow(handle, &children);
+
+        if self.get_component(handle).unwrap().is_scroll_view() {
+            self.layout_scroll_view(handle, &children);
+        }
+
+        // Only children that are dirty themselves, or ended up with a different rect, need to be
+        // arranged again
+        for (index, &child) in children.iter().enumerate() {
+            let child_component = self.get_component(child).unwrap();
+            let moved = child_component.get_outer_rect() != old_rects[index];
+
+            if force || moved || child_component.dirty & (dirty::LAYOUT | dirty::CHILDREN) != 0 {
+                self.arrange_component(child, force || moved);
+            }
+        }
+
+        self.get_component_mut(handle).unwrap().children = children;
+        self.mark_dirty(handle, dirty::PAINT);
+    }
+
+    /// Checks if any of the children stick out of the content rect after the layout provider
+    /// positioned them, and emits [`PreonEvent::OverflowChanged`] if that changed.
+    fn update_overflow(&mut self, handle: PreonComponentHandle, children: &[PreonComponentHandle]) {
+        let content = self.get_component(handle).unwrap().get_content_rect();
+        let mut overflowing_x = false;
+        let mut overflowing_y = false;
+
+        for &child in children.iter() {
+            let rect = self.get_component(child).unwrap().get_outer_rect();
+
+            overflowing_x |= rect.position.x < content.position.x
+                || rect.position.x + rect.size.x > content.position.x + content.size.x;
+            overflowing_y |= rect.position.y < content.position.y
+                || rect.position.y + rect.size.y > content.position.y + content.size.y;
+        }
+
+        let component = self.get_component_mut(handle).unwrap();
+        if component.overflowing_x != overflowing_x || component.overflowing_y != overflowing_y {
+            component.overflowing_x = overflowing_x;
+            component.overflowing_y = overflowing_y;
+            self.events.push(PreonEvent::OverflowChanged(handle));
+        }
+    }
+
+    /// Measures the content of a scroll view after its layout provider positioned the children,
+    /// then moves them by the scroll offset.
+    fn layout_scroll_view(&mut self, handle: PreonComponentHandle, children: &[PreonComponentHandle]) {
+        let origin = self.get_component(handle).unwrap().get_content_position();
+        let mut content_size: PreonVector<i32> = PreonVector::zero();
+
+        for &child in children.iter() {
+            let rect = self.get_component(child).unwrap().get_outer_rect();
+            let end = rect.position + rect.size - origin;
+
+            content_size = PreonVector::new(content_size.x.max(end.x), content_size.y.max(end.y));
+        }
+
+        let component = self.get_component_mut(handle).unwrap();
+        component.scroll.as_mut().unwrap().content_size = content_size;
+
+        // The content might have shrunk
+        let offset = component.clamp_scroll_offset(component.scroll.as_ref().unwrap().offset);
+        let applied_offset = PreonVector::new(libm::roundf(offset.x) as i32, libm::roundf(offset.y) as i32);
+
+        let scroll = component.scroll.as_mut().unwrap();
+        scroll.offset = offset;
+        scroll.applied_offset = applied_offset;
+
+        for &child in children.iter() {
+            let child = self.get_component_mut(child).unwrap();
+            child.set_outer_position(child.get_outer_position() - applied_offset);
+        }
+    }
+
+    /// The render order: every component followed by its descendants, see [`PreonDisplayList::iter`].
+    fn display_steps(&self) -> Vec<PreonDisplayStep> {
+        let mut steps = Vec::with_capacity(self.components.len() * 2);
+        let mut stack = vec![PreonDisplayStep::Component(self.root)];
+
+        while let Some(step) = stack.pop() {
+            steps.push(step);
+
+            if let PreonDisplayStep::Component(handle) = step {
+                stack.push(PreonDisplayStep::AfterChildren(handle));
+                stack.extend(
+                    self.get_component(handle)
+                        .unwrap()
+                        .children
+                        .iter()
+                        .rev()
+                        .map(|&child| PreonDisplayStep::Component(child)),
+                );
+            }
+        }
+
+        steps
+    }
+
+    /// Measures the text of the text input `handle` where it's currently laid out. Text areas whose
+    /// text changed height are laid out again to grow with it.
+    fn measure_text_input(&mut self, handle: PreonComponentHandle) {
+        let component = self.components.get_mut(handle).unwrap();
+        let size = component.get_content_size();
+
+        let resized = match component.text_input {
+            Some(ref mut state) => {
+                state.measure(&component.text, &component.style.text_style, &*self.text_measurer, size)
+                    && state.multiline
+            }
+            None => false,
+        };
+
+        if resized {
+            self.mark_dirty(handle, dirty::LAYOUT);
+        }
+    }
+
+    /// Renders every component marked with [`dirty::PAINT`] into the display list.
+    fn update_display_list(&mut self) {
+        if core::mem::take(&mut self.reorder_requested) {
+            let steps = self.display_steps();
+            self.display_list.set_order(steps);
+        }
+
+        for handle in core::mem::take(&mut self.paint_queue) {
+            // Skip components that have been removed since
+            if !self.components.contains(handle) {
+                continue;
+            }
+
+            let opacity = self.inherited_opacity(handle);
+            let component = self.components.get_mut(handle).unwrap();
+            component.dirty &= !dirty::PAINT;
+
+            // Text inputs need to know where the characters are to draw the caret
+            if component.is_selectable() {
+                self.measure_text_input(handle);
+            }
+
+            let component = self.components.get_mut(handle).unwrap();
+            let mut shapes = Vec::new();
+            let mut after_children = Vec::new();
+            component.render(&mut shapes);
+            component.render_after_children(&mut after_children);
+
+            if opacity < 1.0 {
+                for shape in shapes.iter_mut().chain(after_children.iter_mut()) {
+                    match *shape {
+                        // Images aren't faded
+                        PreonShape::Rect {
+                            ref mut color,
+                            index: None,
+                            ..
+                        }
+                        | PreonShape::Text { ref mut color, .. } => color.a *= opacity,
+                        _ => (),
+                    }
+                }
+            }
+
+            self.display_list.set(handle, shapes, after_children);
+        }
+    }
+
+    pub fn load_image(&mut self, image: impl IntoImage) -> PreonImage {
+        self.renderer_load_ops.textures.push(image.get_image());
+        self.image_references
+            .push(Rc::new(RefCell::new(self.image_references.len())));
+        PreonImage::new(self.image_references[self.image_references.len() - 1].clone())
+    }
+
+    pub fn unload_image(&mut self, image: PreonImage) {
+        let index = image.index();
+
+        self.renderer_load_ops.unload_textures.push(index);
+        self.image_references.remove(
+            self.image_references
+                .iter()
+                .position(|r| *r.borrow() == index)
+                .unwrap(),
+        );
+
+        for image_ref in self.image_references.iter_mut() {
+            if *image_ref.borrow() > index {
+                *image_ref.borrow_mut() -= 1;
+            }
+        }
+    }
+
+    pub fn load_font(&mut self, font: impl IntoFont) -> PreonFont {
+        self.renderer_load_ops.fonts.push(font.get_font());
+        self.font_references
+            .push(Rc::new(RefCell::new(self.font_references.len())));
+        PreonFont::new(self.font_references[self.font_references.len() - 1].clone())
+    }
+
+    pub fn unload_font(&mut self, font: PreonFont) {
+        let index = font.index();
+
+        self.renderer_load_ops.unload_fonts.push(index);
+        self.font_references.remove(
+            self.font_references
+                .iter()
+                .position(|r| *r.borrow() == index)
+                .unwrap(),
+        );
+
+        for font_ref in self.font_references.iter_mut() {
+            if *font_ref.borrow() > index {
+                *font_ref.borrow_mut() -= 1;
+            }
+        }
+    }
+
+    /// Replaces the measurer used to find out where characters are drawn, render modules should
+    /// set one that matches how they draw text. Defaults to [`PreonApproximateTextMeasurer`].
+    pub fn set_text_measurer(&mut self, measurer: impl PreonTextMeasurer + 'static) {
+        self.text_measurer = Box::new(measurer);
+        self.mark_subtree_dirty(self.root, dirty::PAINT);
+    }
+
+    #[inline]
+    pub fn text_measurer(&self) -> &dyn PreonTextMeasurer {
+        &*self.text_measurer
+    }
+
+    /// Replaces the clipboard text is copied to and pasted from, render modules should set the one
+    /// of the system. Defaults to a [`PreonMemoryClipboard`].
+    pub fn set_clipboard(&mut self, clipboard: impl PreonClipboard + 'static) {
+        self.clipboard = Box::new(clipboard);
+    }
+
+    #[inline]
+    pub fn clipboard(&mut self) -> &mut dyn PreonClipboard {
+        &mut *self.clipboard
+    }
+
+    /// Returns true if a component has been marked dirty since the last `engine.update()`, or if
+    /// a scroll view is still scrolling by itself, or an animation is running. Render modules should schedule another update
+    /// (with a [`PreonUserEvent::Tick`]) when this is true, even without new user events.
+    /// Otherwise they can wait for user input, or until [`PreonEngine::next_timer_due`].
+    pub fn needs_update(&self) -> bool {
+        self.relayout_requested
+            || self.repaint_requested
+            || !self.scrolling.is_empty()
+            || self
+                .animated
+                .iter()
+                .any(|&handle| self.get_component(handle).unwrap().is_animating())
+    }
+
+    pub fn update(&mut self, user_events: &PreonEventEmitter<PreonUserEvent>) -> bool {
+        if user_events.is_empty() && self.events.is_empty() && !self.needs_update() {
+            return false;
+        }
+
+        self.display_list.begin_frame();
+
+        let mut update_hover = false;
+
+        for event in user_events.take() {
+            match event {
+                PreonUserEvent::Tick(delta) => {
+                    self.time += delta;
+                    self.update_inertia(delta);
+                    self.fire_timers();
+                }
+                PreonUserEvent::WindowResized(new_size) => {
+                    if new_size != self.window_inner_size {
+                        self.window_inner_size = new_size;
+                        self.events.push(PreonEvent::WindowResized(new_size));
+                        self.mark_dirty(self.root, dirty::LAYOUT);
+                        self.display_list.add_damage(PreonRect::new(
+                            PreonVector::zero(),
+                            PreonVector::new(new_size.x as i32, new_size.y as i32),
+                        ));
+                    }
+                }
+                PreonUserEvent::ForceUpdate => self.mark_dirty(self.root, dirty::LAYOUT),
+                PreonUserEvent::WindowOpened => {
+                    self.events.push(PreonEvent::WindowOpened);
+                    self.mark_dirty(self.root, dirty::LAYOUT);
+                }
+                PreonUserEvent::WindowClosed => {
+                    self.events.push(PreonEvent::WindowClosed);
+                }
+                PreonUserEvent::WindowFocusLost => {
+                    self.pressed_keys.clear();
+                    self.modifiers = PreonModifiers::NONE;
+                }
+                PreonUserEvent::MouseMove(mouse_position) => {
+                    self.mouse_position = mouse_position;
+                    self.mouse_in_window = true;
+                    self.drag_scrollbar();
+                    self.drag_text_selection();
+                    update_hover = true;
+                }
+                PreonUserEvent::MouseLeftWindow => {
+                    self.mouse_in_window = false;
+                    update_hover = true;
+                }
+                PreonUserEvent::MouseInput(button, state) => {
+                    self.events
+                        .push(PreonEvent::MouseInput(button, state, self.modifiers));
+                    self.mouse_input(button, state);
+                }
+                PreonUserEvent::MouseWheel(delta) => {
+                    // Outside of the window (or without any components) scrolling goes to the root
+                    let event = PreonScrollEvent {
+                        target: self.component_at(self.mouse_position).unwrap_or(self.root),
+                        delta,
+                        position: self.mouse_position,
+                        modifiers: self.modifiers,
+                    };
+
+                    if !self.dispatch(event.target, PreonComponentEvent::Scroll(event))
+                        && !self.scroll_wheel(event)
+                    {
+                        self.events.push(PreonEvent::Scroll(event));
+                    }
+                }
+                PreonUserEvent::ModifiersChanged(modifiers) => self.modifiers = modifiers,
+                PreonUserEvent::KeyboardInput {
+                    key,
+                    scancode,
+                    state,
+                } => {
+                    if let Some(modifier) = PreonModifiers::from_key(key) {
+                        self.modifiers
+                            .set(modifier, matches!(state, PreonButtonState::Pressed));
+                    }
+
+                    // Held down keys are pressed again without being released
+                    let is_repeat = match state {
+                        PreonButtonState::Pressed if self.pressed_keys.contains(&key) => true,
+                        PreonButtonState::Pressed => {
+                            self.pressed_keys.push(key);
+                            false
+                        }
+                        PreonButtonState::Released => {
+                            self.pressed_keys.retain(|&pressed| pressed != key);
+                            false
+                        }
+                    };
+
+                    self.keyboard_input(PreonKeyEvent {
+                        key,
+                        scancode,
+                        state,
+                        is_repeat,
+                        modifiers: self.modifiers,
+                    });
+                }
+                PreonUserEvent::ReceivedCharacter(ch) => {
+                    self.events.push(PreonEvent::ReceivedCharacter(ch));
+
+                    let target = self.focused.unwrap_or(self.root);
+                    if self.dispatch(target, PreonComponentEvent::ReceivedCharacter(ch)) {
+                        continue;
+                    }
+
+                    // Tab is handled by focus navigation
+                    match self.focused {
+                        Some(focused) if ch != '\t' => {
+                            self.events
+                                .push(PreonEvent::ComponentReceivedCharacter(focused, ch));
+
+                            // Control characters (Backspace, Return, Ctrl+A, ...) are handled as keys.
+                            // AltGr is sent as Ctrl+Alt on some platforms, and does type characters.
+                            let shortcut = self.modifiers.logo()
+                                || (self.modifiers.ctrl() && !self.modifiers.alt());
+                            if !ch.is_control() && !shortcut {
+                                self.insert_text(focused, ch.encode_utf8(&mut [0; 4]));
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                PreonUserEvent::ImePreedit(preedit, cursor) => {
+                    self.events
+                        .push(PreonEvent::ImePreedit(preedit.clone(), cursor.clone()));
+                    self.ime_preedit(preedit, cursor);
+                }
+                PreonUserEvent::ImeCommit(text) => {
+                    self.events.push(PreonEvent::ImeCommit(text.clone()));
+                    self.ime_commit(&text);
+                }
+            }
+        }
+
+        self.update_animations();
+
+        if core::mem::take(&mut self.relayout_requested) {
+            log::info!("Relayout");
+
+            let window_inner_size = PreonVector::new(
+                self.window_inner_size.x as i32,
+                self.window_inner_size.y as i32,
+            );
+
+            let tree = self.get_component_mut(self.root).unwrap();
+            tree.set_outer_size(window_inner_size);
+            tree.set_outer_position(PreonVector::zero());
+
+            self.layout_component(self.root, false);
+
+            self.events.push(PreonEvent::LayoutUpdate);
+
+            // Components might have moved under the mouse
+            update_hover = true;
+        }
+
+        // Scrolling moves components without a relayout
+        if update_hover || core::mem::take(&mut self.hover_outdated) {
+            self.update_hover();
+
+            // Hovering can start transitions
+            self.update_animations();
+        }
+
+        // Also set by the relayout, for every component that has been laid out
+        if core::mem::take(&mut self.repaint_requested) {
+            self.update_display_list();
+        }
+
+        // Carets are measured while painting, and move with scrolling
+        self.update_ime_cursor_area();
+
+        self.display_list.end_frame();
+
+        self.events.push(PreonEvent::Update);
+        self.events.flip();
+
+        true
+    }
+}
+
+/// Reference to a component inside of a [`PreonEngine`]. Handles stay valid until their component
+/// is removed, after which [`PreonEngine::get_component`] returns `None` for them, even if the
+/// underlying slot gets reused.
+///
+/// `repr(C)`, so language bindings can pass handles around by value.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PreonComponentHandle {
+    pub(crate) index: u32,
+    pub(crate) generation: u32,
+}
+
+impl PreonComponentHandle {
+    pub(crate) fn new(index: u32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Contains all the necessary imports to quickly build an app with PreonEngine
+pub mod prelude {
+    pub use crate::animation::PreonAnimatable;
+    pub use crate::animation::PreonAnimationValue;
+    pub use crate::animation::PreonEasing;
+    pub use crate::animation::PreonKeyframes;
+    pub use crate::clipboard::PreonClipboard;
+    pub use crate::components::button::PreonComponentBuilderButtonExtension;
+    pub use crate::components::hbox::PreonComponentBuilderHBoxExtension;
+    pub use crate::components::label::PreonComponentBuilderLabelExtension;
+    pub use crate::components::panel::PreonComponentBuilderPanelExtension;
+    pub use crate::components::scroll_view::PreonComponentBuilderScrollViewExtension;
+    pub use crate::components::static_texture::PreonComponentBuilderStaticTextureExtension;
+    pub use crate::components::text_area::PreonComponentBuilderTextAreaExtension;
+    pub use crate::components::text_input::PreonComponentBuilderTextInputExtension;
+    pub use crate::components::vbox::PreonComponentBuilderVBoxExtension;
+    pub use crate::components::PreonComponentBuilder;
+    pub use crate::events::PreonEvent;
+    pub use crate::events::PreonEventKind;
+    pub use crate::events::PreonUserEvent;
+    pub use crate::rendering::PreonFont;
+    pub use crate::rendering::PreonImage;
+    pub use crate::size;
+    pub use crate::style::PreonBackground;
+    pub use crate::style::PreonClass;
+    pub use crate::style::PreonComponentBuilderStyleExtension;
+    pub use crate::style::PreonComponentBuilderTextStyleExtension;
+    pub use crate::style::PreonOverflow;
+    pub use crate::types::*;
+    pub use crate::PreonEngine;
+}
+
+/// Replaces the log crate
+#[cfg(not(feature = "logging"))]
+pub mod log {
+    pub use crate::{error, info, log_enabled, warn};
+    #[macro_export]
+    macro_rules! log_enabled {
+        (target: $target:expr, $lvl:expr) => {
+            false
+        };
+        ($lvl:expr) => {
+            false
+        };
+    }
+    #[macro_export]
+    macro_rules! info {
+        (target: $target:expr, $($arg:tt)+) => {{};};
+        ($($arg:tt)+) => {{};};
+    }
+    #[macro_export]
+    macro_rules! warn {
+        (target: $target:expr, $($arg:tt)+) => {{};};
+        ($($arg:tt)+) => {{};};
+    }
+    #[macro_export]
+    macro_rules! error {
+        (target: $target:expr, $($arg:tt)+) => {{};};
+        ($($arg:tt)+) => {{};};
+    }
+}
+
+#[cfg(feature = "logging")]
+pub use log;
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use alloc::vec::Vec;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::{
+        components::PreonComponent,
+        dirty,
+        events::{
+            PreonButtonState, PreonEvent, PreonEventEmitter, PreonEventKind, PreonKeyCode,
+            PreonModifiers, PreonScrollDelta, PreonUserEvent,
+        },
+        types::PreonVector,
+        PreonComponentHandle, PreonEngine,
+    };
+
+    /// The root with children `a` and `b`, and `a` with children `a1` and `a2`.
+    fn tree() -> (PreonEngine, [PreonComponentHandle; 4]) {
+        let mut engine = PreonEngine::new();
+        let root = engine.root;
+
+        let a = engine.push_component(root, PreonComponent::new());
+        let b = engine.push_component(root, PreonComponent::new());
+        let a1 = engine.push_component(a, PreonComponent::new());
+        let a2 = engine.push_component(a, PreonComponent::new());
+
+        (engine, [a, b, a1, a2])
+    }
+
+    fn children(engine: &PreonEngine, handle: PreonComponentHandle) -> Vec<PreonComponentHandle> {
+        engine.get_component(handle).unwrap().children.clone()
+    }
+
+    fn parent(engine: &PreonEngine, handle: PreonComponentHandle) -> Option<PreonComponentHandle> {
+        engine.get_component(handle).unwrap().parent
+    }
+
+    fn panics(f: impl FnOnce()) -> bool {
+        catch_unwind(AssertUnwindSafe(f)).is_err()
+    }
+
+    /// Runs an update with `user_events`, or a resize to 800x600 if there are none.
+    fn update(engine: &mut PreonEngine, user_events: &[PreonUserEvent]) {
+        let mut emitter = PreonEventEmitter::new();
+        if user_events.is_empty() {
+            emitter.push(PreonUserEvent::WindowResized(PreonVector::new(800, 600)));
+        }
+        for event in user_events {
+            emitter.push(event.clone());
+        }
+        emitter.flip();
+
+        engine.update(&emitter);
+    }
+
+    #[test]
+    fn removing_a_component_removes_its_subtree() {
+        let (mut engine, [a, b, a1, a2]) = tree();
+        let root = engine.root;
+
+        assert!(engine.remove_component(a).is_some());
+        assert_eq!(children(&engine, root), [b]);
+        for handle in [a, a1, a2] {
+            assert!(engine.get_component(handle).is_none());
+        }
+
+        // The root can't be removed, and stale handles are ignored, even after their slot is reused
+        assert!(engine.remove_component(root).is_none());
+        assert!(engine.remove_component(a).is_none());
+        let c = engine.push_component(root, PreonComponent::new());
+        assert!(engine.remove_component(a1).is_none());
+        assert!(engine.remove_component(a2).is_none());
+        assert_eq!(children(&engine, root), [b, c]);
+    }
+
+    #[test]
+    fn reparent_moves_the_subtree() {
+        let (mut engine, [a, b, a1, a2]) = tree();
+        let root = engine.root;
+
+        engine.reparent(a, b, 0);
+        assert_eq!(children(&engine, root), [b]);
+        assert_eq!(children(&engine, b), [a]);
+        assert_eq!(parent(&engine, a), Some(b));
+        assert_eq!(children(&engine, a), [a1, a2]);
+
+        // An index past the end appends
+        engine.reparent(a2, root, 100);
+        assert_eq!(children(&engine, root), [b, a2]);
+        engine.reparent(a1, root, 1);
+        assert_eq!(children(&engine, root), [b, a1, a2]);
+        assert!(children(&engine, a).is_empty());
+    }
+
+    #[test]
+    fn reparenting_into_its_own_subtree_fails() {
+        let (mut engine, [a, b, a1, a2]) = tree();
+        let root = engine.root;
+
+        assert!(panics(|| engine.reparent(a, a1, 0)));
+        assert!(panics(|| engine.reparent(a, a, 0)));
+        assert!(panics(|| engine.reparent(root, b, 0)));
+
+        // A failed move leaves the tree intact
+        assert_eq!(children(&engine, root), [a, b]);
+        assert_eq!(children(&engine, a), [a1, a2]);
+        assert_eq!(parent(&engine, a), Some(root));
+    }
+
+    #[test]
+    fn move_before_and_after() {
+        let (mut engine, [a, b, a1, a2]) = tree();
+        let root = engine.root;
+        let c = engine.push_component(root, PreonComponent::new());
+
+        engine.move_after(a, c);
+        assert_eq!(children(&engine, root), [b, c, a]);
+        engine.move_before(a, b);
+        assert_eq!(children(&engine, root), [a, b, c]);
+        engine.move_after(c, a);
+        assert_eq!(children(&engine, root), [a, c, b]);
+
+        // Moving next to a component with another parent changes the parent
+        engine.move_before(a2, b);
+        assert_eq!(children(&engine, root), [a, c, a2, b]);
+        assert_eq!(children(&engine, a), [a1]);
+        assert_eq!(parent(&engine, a2), Some(root));
+
+        // Moving next to itself does nothing
+        engine.move_after(b, b);
+        assert_eq!(children(&engine, root), [a, c, a2, b]);
+
+        assert!(panics(|| engine.move_after(a, a1)));
+        assert!(panics(|| engine.move_before(a, root)));
+        assert_eq!(children(&engine, root), [a, c, a2, b]);
+    }
+
+    #[test]
+    fn swap_children() {
+        let (mut engine, [a, b, a1, a2]) = tree();
+        let root = engine.root;
+
+        engine.swap_children(root, 0, 1);
+        assert_eq!(children(&engine, root), [b, a]);
+        engine.swap_children(a, 1, 1);
+        assert_eq!(children(&engine, a), [a1, a2]);
+
+        assert!(panics(|| engine.swap_children(a, 0, 2)));
+        assert_eq!(children(&engine, a), [a1, a2]);
+    }
+
+    #[test]
+    fn clone_subtree_copies_every_descendant() {
+        let (mut engine, [a, b, a1, a2]) = tree();
+        engine.get_component_mut(a1).unwrap().text = "a1".into();
+
+        let copy = engine.clone_subtree(a, b);
+        assert_eq!(children(&engine, b), [copy]);
+        assert_eq!(parent(&engine, copy), Some(b));
+
+        let copies = children(&engine, copy);
+        assert_eq!(copies.len(), 2);
+        assert!(!copies.contains(&a1) && !copies.contains(&a2));
+        assert_eq!(engine.get_component(copies[0]).unwrap().text, "a1");
+        assert_eq!(parent(&engine, copies[0]), Some(copy));
+
+        // The original is untouched
+        assert_eq!(children(&engine, a), [a1, a2]);
+
+        // Cloning into its own subtree only copies what was there before
+        let nested = engine.clone_subtree(a, a2);
+        assert_eq!(children(&engine, a2), [nested]);
+        assert_eq!(engine.depth_first(a).count(), 6);
+    }
+
+    #[test]
+    fn detach_keeps_the_component() {
+        let (mut engine, [a, _, a1, a2]) = tree();
+        let root = engine.root;
+
+        engine.detach(a1);
+        assert_eq!(children(&engine, a), [a2]);
+        assert_eq!(parent(&engine, a1), None);
+        assert!(engine.get_component(a1).is_some());
+
+        // The root has no parent to detach from
+        engine.detach(root);
+        assert_eq!(children(&engine, root).len(), 2);
+    }
+
+    #[test]
+    fn stale_handles_are_rejected() {
+        let (mut engine, [a, b, a1, _]) = tree();
+        let root = engine.root;
+
+        engine.remove_component(a);
+        // Reuses the slot of one of the removed components
+        let c = engine.push_component(root, PreonComponent::new());
+
+        assert!(panics(|| engine.reparent(a1, c, 0)));
+        assert!(panics(|| engine.reparent(c, a, 0)));
+        assert!(panics(|| engine.move_before(a, c)));
+        assert!(panics(|| engine.move_after(c, a1)));
+        assert!(panics(|| engine.swap_children(a, 0, 1)));
+        assert!(panics(|| {
+            engine.clone_subtree(a, root);
+        }));
+        assert!(panics(|| {
+            engine.clone_subtree(c, a);
+        }));
+        assert!(panics(|| engine.detach(a1)));
+
+        assert_eq!(children(&engine, root), [b, c]);
+        assert!(children(&engine, c).is_empty());
+    }
+
+    fn key(key: PreonKeyCode, state: PreonButtonState) -> PreonUserEvent {
+        PreonUserEvent::KeyboardInput {
+            key,
+            scancode: 0,
+            state,
+        }
+    }
+
+    /// The key, repeat flag and modifiers of every key event sent by the last update.
+    fn key_events(engine: &PreonEngine) -> Vec<(PreonKeyCode, bool, PreonModifiers)> {
+        engine
+            .events
+            .take()
+            .into_iter()
+            .filter_map(|event| match event {
+                PreonEvent::KeyboardInput(event) => Some((event.key, event.is_repeat, event.modifiers)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn held_down_keys_repeat() {
+        use PreonButtonState::{Pressed, Released};
+        use PreonKeyCode::{A, B};
+
+        let mut engine = PreonEngine::new();
+        let none = PreonModifiers::NONE;
+
+        update(&mut engine, &[key(A, Pressed), key(B, Pressed), key(A, Pressed)]);
+        assert_eq!(key_events(&engine), [(A, false, none), (B, false, none), (A, true, none)]);
+
+        update(&mut engine, &[key(B, Pressed), key(A, Released), key(A, Pressed)]);
+        assert_eq!(key_events(&engine), [(B, true, none), (A, false, none), (A, false, none)]);
+    }
+
+    #[test]
+    fn losing_focus_forgets_held_down_keys() {
+        use PreonButtonState::Pressed;
+        use PreonKeyCode::{LShift, A};
+
+        let mut engine = PreonEngine::new();
+        let shift = PreonModifiers::SHIFT;
+
+        update(&mut engine, &[key(LShift, Pressed), key(A, Pressed)]);
+        assert_eq!(key_events(&engine), [(LShift, false, shift), (A, false, shift)]);
+        assert_eq!(engine.modifiers(), shift);
+
+        // Both keys are released while another window has focus
+        update(&mut engine, &[PreonUserEvent::WindowFocusLost]);
+        assert_eq!(engine.modifiers(), PreonModifiers::NONE);
+
+        update(&mut engine, &[key(A, Pressed)]);
+        assert_eq!(key_events(&engine), [(A, false, PreonModifiers::NONE)]);
+    }
+
+    #[test]
+    fn scroll_listeners_can_remove_their_target() {
+        let mut engine = PreonEngine::new();
+        let root = engine.root;
+
+        let mut component = PreonComponent::new();
+        component.style.min_size = PreonVector::new(100, 100);
+        let target = engine.push_component(root, component);
+        engine.on(target, PreonEventKind::Scroll, |ctx| {
+            let target = ctx.target();
+            ctx.engine.remove_component(target);
+        });
+
+        update(&mut engine, &[]);
+        update(
+            &mut engine,
+            &[
+                PreonUserEvent::MouseMove(PreonVector::new(400, 50)),
+                PreonUserEvent::MouseWheel(PreonScrollDelta::Lines(PreonVector::new(0.0, -1.0))),
+            ],
+        );
+
+        assert!(engine.get_component(target).is_none());
+        assert!(engine
+            .events
+            .take()
+            .iter()
+            .any(|event| matches!(event, PreonEvent::Scroll(event) if event.target == target)));
+    }
+
+    #[test]
+    fn mark_dirty_propagates_to_the_root() {
+        let (mut engine, [a, b, a1, a2]) = tree();
+        let root = engine.root;
+        update(&mut engine, &[]);
+
+        for handle in [root, a, b, a1, a2] {
+            assert_eq!(engine.get_component(handle).unwrap().dirty, 0);
+        }
+
+        engine.mark_dirty(a1, dirty::PAINT);
+        assert_eq!(engine.get_component(a1).unwrap().dirty, dirty::PAINT);
+        assert_eq!(engine.get_component(a).unwrap().dirty, 0);
+
+        engine.mark_dirty(a1, dirty::LAYOUT);
+        assert_eq!(engine.get_component(a1).unwrap().dirty, dirty::PAINT | dirty::LAYOUT);
+        assert_eq!(engine.get_component(a).unwrap().dirty, dirty::CHILDREN);
+        assert_eq!(engine.get_component(root).unwrap().dirty, dirty::CHILDREN);
+        assert_eq!(engine.get_component(a2).unwrap().dirty, 0);
+        assert_eq!(engine.get_component(b).unwrap().dirty, 0);
+
+        update(&mut engine, &[]);
+        for handle in [root, a, b, a1, a2] {
+            assert_eq!(engine.get_component(handle).unwrap().dirty, 0);
+        }
+    }
+
+    #[test]
+    fn only_dirty_subtrees_are_measured() {
+        let (mut engine, [a, b, a1, _]) = tree();
+        update(&mut engine, &[]);
+
+        // Changes that aren't marked dirty survive the next layout of another subtree
+        let size = PreonVector::new(100, 100);
+        engine.get_component_mut(b).unwrap().set_inner_size(size);
+        engine.edit_style(a1, |style| style.min_size = PreonVector::new(10, 10));
+        update(&mut engine, &[]);
+
+        assert_eq!(engine.get_component(a1).unwrap().get_inner_size(), PreonVector::new(10, 10));
+        assert_eq!(engine.get_component(a).unwrap().get_inner_size(), PreonVector::new(10, 10));
+        assert_eq!(engine.get_component(b).unwrap().get_inner_size(), size);
+
+        engine.mark_dirty(b, dirty::LAYOUT);
+        update(&mut engine, &[]);
+        assert_eq!(engine.get_component(b).unwrap().get_inner_size(), PreonVector::zero());
+    }
+
+    #[test]
+    fn fitting_components_shrink_with_their_children() {
+        let (mut engine, [a, _, a1, a2]) = tree();
+        engine.edit_style(a1, |style| style.min_size = PreonVector::new(40, 30));
+        engine.edit_style(a2, |style| style.min_size = PreonVector::new(20, 10));
+        update(&mut engine, &[]);
+        assert_eq!(engine.get_component(a).unwrap().get_inner_size(), PreonVector::new(40, 40));
+
+        engine.edit_style(a1, |style| style.min_size = PreonVector::new(10, 10));
+        update(&mut engine, &[]);
+        assert_eq!(engine.get_component(a).unwrap().get_inner_size(), PreonVector::new(20, 20));
+
+        engine.remove_component(a2);
+        update(&mut engine, &[]);
+        assert_eq!(engine.get_component(a).unwrap().get_inner_size(), PreonVector::new(10, 10));
+    }
+}
@@ -0,0 +1,296 @@
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+use crate::{
+    rendering::PreonShape,
+    types::{PreonRect, PreonVector},
+    PreonComponentHandle,
+};
+
+/// Above this amount, damaged rectangles are merged into a single one.
+const MAX_DAMAGE_RECTS: usize = 16;
+
+/// What happened to the shapes of a component during the last `engine.update()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreonDisplayListChange {
+    Added(PreonComponentHandle),
+    Updated(PreonComponentHandle),
+    Removed(PreonComponentHandle),
+}
+
+/// One entry of the render order, see [`PreonDisplayList::iter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PreonDisplayStep {
+    Component(PreonComponentHandle),
+    /// Comes after all descendants of the component.
+    AfterChildren(PreonComponentHandle),
+}
+
+#[derive(Debug, Clone)]
+struct PreonDisplayItem {
+    shapes: Vec<PreonShape>,
+    /// Drawn on top of the descendants, e.g. popping a clip or scrollbars.
+    after_children: Vec<PreonShape>,
+    bounds: Option<PreonRect<i32>>,
+}
+
+/// The shapes of every component, kept between updates.
+///
+/// Only components that changed are rendered again, after `engine.update()` [`PreonDisplayList::changes`]
+/// lists which components were added, updated or removed, and [`PreonDisplayList::damage`] which parts
+/// of the screen need to be repainted. A render module can skip frames without damage entirely.
+///
+/// ```ignore
+/// if engine.update(&user_events) && engine.display_list.is_damaged() {
+///     for shape in engine.display_list.shapes() {
+///         // draw the shape, optionally clipped to `engine.display_list.damage_bounds()`
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PreonDisplayList {
+    items: HashMap<PreonComponentHandle, PreonDisplayItem>,
+    order: Vec<PreonComponentHandle>,
+    steps: Vec<PreonDisplayStep>,
+    changes: Vec<PreonDisplayListChange>,
+    damage: Vec<PreonRect<i32>>,
+    /// Set after an update, the changes and damage are cleared on the next modification.
+    finished: bool,
+}
+
+impl PreonDisplayList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shapes of `handle`, `None` if it isn't part of the list.
+    pub fn get(&self, handle: PreonComponentHandle) -> Option<&[PreonShape]> {
+        self.items.get(&handle).map(|item| item.shapes.as_slice())
+    }
+
+    /// The shapes `handle` draws on top of its descendants, `None` if it isn't part of the list.
+    pub fn get_after_children(&self, handle: PreonComponentHandle) -> Option<&[PreonShape]> {
+        self.items.get(&handle).map(|item| item.after_children.as_slice())
+    }
+
+    /// The area covered by all shapes of `handle`, `None` if it has no shapes.
+    pub fn bounds(&self, handle: PreonComponentHandle) -> Option<PreonRect<i32>> {
+        self.items.get(&handle).and_then(|item| item.bounds)
+    }
+
+    /// All components in render order, back to front.
+    pub fn order(&self) -> &[PreonComponentHandle] {
+        &self.order
+    }
+
+    /// All components with their shapes, in render order. Components drawing shapes on top of
+    /// their descendants (see [`PreonDisplayList::get_after_children`]) appear a second time,
+    /// after their last descendant.
+    pub fn iter(&self) -> impl Iterator<Item = (PreonComponentHandle, &[PreonShape])> {
+        self.steps.iter().filter_map(move |&step| match step {
+            PreonDisplayStep::Component(handle) => self
+                .items
+                .get(&handle)
+                .map(|item| (handle, item.shapes.as_slice())),
+            PreonDisplayStep::AfterChildren(handle) => self
+                .items
+                .get(&handle)
+                .filter(|item| !item.after_children.is_empty())
+                .map(|item| (handle, item.after_children.as_slice())),
+        })
+    }
+
+    /// All shapes in render order.
+    pub fn shapes(&self) -> impl Iterator<Item = &PreonShape> {
+        self.iter().flat_map(|(_, shapes)| shapes.iter())
+    }
+
+    /// Components that were added, updated or removed during the last `engine.update()`.
+    pub fn changes(&self) -> &[PreonDisplayListChange] {
+        &self.changes
+    }
+
+    /// Parts of the screen that changed during the last `engine.update()`.
+    pub fn damage(&self) -> &[PreonRect<i32>] {
+        &self.damage
+    }
+
+    /// A single rect containing all of [`PreonDisplayList::damage`].
+    pub fn damage_bounds(&self) -> Option<PreonRect<i32>> {
+        self.damage.iter().copied().reduce(|a, b| a.union(&b))
+    }
+
+    #[inline]
+    pub fn is_damaged(&self) -> bool {
+        !self.damage.is_empty()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Forgets the changes and damage of the previous update, if that hasn't happened yet.
+    pub(crate) fn begin_frame(&mut self) {
+        if self.finished {
+            self.finished = false;
+            self.changes.clear();
+            self.damage.clear();
+        }
+    }
+
+    /// Keeps the current changes and damage around until the next modification.
+    pub(crate) fn end_frame(&mut self) {
+        self.finished = true;
+    }
+
+    /// Replaces the shapes of `handle`, damaging its old and new bounds if they changed.
+    pub(crate) fn set(
+        &mut self,
+        handle: PreonComponentHandle,
+        shapes: Vec<PreonShape>,
+        after_children: Vec<PreonShape>,
+    ) {
+        self.begin_frame();
+
+        let bounds = Self::item_bounds(shapes.iter().chain(after_children.iter()));
+
+        let old_bounds = match self.items.get_mut(&handle) {
+            Some(item) if item.shapes == shapes && item.after_children == after_children => return,
+            Some(item) => {
+                self.changes.push(PreonDisplayListChange::Updated(handle));
+                let old_bounds = item.bounds;
+
+                item.shapes = shapes;
+                item.after_children = after_children;
+                item.bounds = bounds;
+
+                old_bounds
+            }
+            None => {
+                self.changes.push(PreonDisplayListChange::Added(handle));
+                self.items.insert(
+                    handle,
+                    PreonDisplayItem {
+                        shapes,
+                        after_children,
+                        bounds,
+                    },
+                );
+
+                None
+            }
+        };
+
+        match (old_bounds, bounds) {
+            (Some(old_bounds), Some(bounds)) if old_bounds == bounds => self.add_damage(bounds),
+            (old_bounds, bounds) => {
+                old_bounds.into_iter().chain(bounds).for_each(|rect| self.add_damage(rect))
+            }
+        }
+    }
+
+    /// The area covered by `shapes`, limited to the clips they push. The clips themselves count
+    /// too, they decide how much of the descendants is visible.
+    fn item_bounds<'a>(shapes: impl Iterator<Item = &'a PreonShape>) -> Option<PreonRect<i32>> {
+        let mut clips: Vec<PreonRect<i32>> = Vec::new();
+        let mut bounds: Option<PreonRect<i32>> = None;
+
+        for shape in shapes {
+            let rect = match *shape {
+                PreonShape::PushClip { position, size, .. } => {
+                    // Clips nest, nothing is visible outside of the outer one
+                    let clip = match clips.last() {
+                        Some(outer) => outer
+                            .intersection(&PreonRect::new(position, size))
+                            .unwrap_or(PreonRect::new(position, PreonVector::new(0, 0))),
+                        None => PreonRect::new(position, size),
+                    };
+                    clips.push(clip);
+                    Some(clip)
+                }
+                PreonShape::PopClip => {
+                    clips.pop();
+                    None
+                }
+                _ => shape.bounds().and_then(|rect| match clips.last() {
+                    Some(clip) => rect.intersection(clip),
+                    None => Some(rect),
+                }),
+            };
+
+            if let Some(rect) = rect.filter(|rect| !rect.is_empty()) {
+                bounds = Some(match bounds {
+                    Some(bounds) => bounds.union(&rect),
+                    None => rect,
+                });
+            }
+        }
+
+        bounds
+    }
+
+    pub(crate) fn remove(&mut self, handle: PreonComponentHandle) {
+        self.begin_frame();
+
+        if let Some(item) = self.items.remove(&handle) {
+            self.changes.push(PreonDisplayListChange::Removed(handle));
+
+            if let Some(bounds) = item.bounds {
+                self.add_damage(bounds);
+            }
+        }
+    }
+
+    /// Changes the render order, damaging every component that moved.
+    pub(crate) fn set_order(&mut self, steps: Vec<PreonDisplayStep>) {
+        if steps == self.steps {
+            return;
+        }
+
+        self.begin_frame();
+
+        for (index, &step) in steps.iter().enumerate() {
+            if self.steps.get(index) != Some(&step) {
+                let handle = match step {
+                    PreonDisplayStep::Component(handle) => handle,
+                    PreonDisplayStep::AfterChildren(handle) => handle,
+                };
+
+                if let Some(bounds) = self.bounds(handle) {
+                    self.add_damage(bounds);
+                }
+            }
+        }
+
+        self.order = steps
+            .iter()
+            .filter_map(|&step| match step {
+                PreonDisplayStep::Component(handle) => Some(handle),
+                PreonDisplayStep::AfterChildren(_) => None,
+            })
+            .collect();
+        self.steps = steps;
+    }
+
+    pub(crate) fn add_damage(&mut self, rect: PreonRect<i32>) {
+        self.begin_frame();
+
+        if rect.is_empty() || self.damage.iter().any(|damage| damage.union(&rect) == *damage) {
+            return;
+        }
+
+        self.damage.push(rect);
+
+        if self.damage.len() > MAX_DAMAGE_RECTS {
+            let bounds = self.damage_bounds().unwrap();
+            self.damage.clear();
+            self.damage.push(bounds);
+        }
+    }
+}
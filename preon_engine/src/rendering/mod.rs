@@ -3,12 +3,18 @@ use core::cell::RefCell;
 use alloc::{string::String, vec::{Vec, Drain}, rc::Rc};
 
 use crate::{
-    events::PreonEventEmitter,
-    types::{PreonColor, PreonVector, PreonCorners}, style::PreonTextStyle,
+    types::{PreonColor, PreonVector, PreonCorners, PreonRect}, style::PreonTextStyle,
 };
 
-/// Describe how to render your UI component by pushing some PreonShapes to the PreonRenderPass
-#[derive(Debug, Clone)]
+mod display_list;
+mod text;
+
+pub use display_list::{PreonDisplayList, PreonDisplayListChange};
+pub(crate) use display_list::PreonDisplayStep;
+pub use text::{PreonApproximateTextMeasurer, PreonTextMeasurer};
+
+/// Describe how to render your UI component by pushing some PreonShapes to the PreonDisplayList
+#[derive(Debug, Clone, PartialEq)]
 pub enum PreonShape {
     Rect {
         position: PreonVector<i32>,
@@ -24,6 +30,26 @@ pub enum PreonShape {
         size: PreonVector<i32>,
         text: String,
     },
+    /// Clips every following shape to this rect (and to the clips pushed before it), until the
+    /// matching [`PreonShape::PopClip`]. Use [`PreonCorners::ZERO`] for a plain rectangular clip.
+    PushClip {
+        position: PreonVector<i32>,
+        size: PreonVector<i32>,
+        radius: PreonCorners,
+    },
+    /// Restores the clip from before the last [`PreonShape::PushClip`].
+    PopClip,
+}
+
+impl PreonShape {
+    /// The area covered by this shape, `None` for clip shapes, which don't draw anything.
+    pub fn bounds(&self) -> Option<PreonRect<i32>> {
+        match *self {
+            PreonShape::Rect { position, size, .. } => Some(PreonRect::new(position, size)),
+            PreonShape::Text { position, size, .. } => Some(PreonRect::new(position, size)),
+            PreonShape::PushClip { .. } | PreonShape::PopClip => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +57,12 @@ pub struct PreonFont {
     index: Rc<RefCell<usize>>,
 }
 
+impl PartialEq for PreonFont {
+    fn eq(&self, other: &Self) -> bool {
+        self.index() == other.index()
+    }
+}
+
 impl PreonFont {
     pub(crate) fn new(index: Rc<RefCell<usize>>) -> PreonFont {
         PreonFont { index }
@@ -113,6 +145,3 @@ impl PreonRendererLoadOperations {
         self.unload_fonts.drain(..)
     }
 }
-
-/// Syntax sugar
-pub type PreonRenderPass = PreonEventEmitter<PreonShape>;
@@ -0,0 +1,54 @@
+use crate::style::PreonTextStyle;
+
+/// Measures text for the engine, so it knows where characters end up on screen (e.g. to place the
+/// caret of a text input). Render modules should provide one matching how they draw text, see
+/// [`PreonEngine::set_text_measurer`](crate::PreonEngine::set_text_measurer).
+pub trait PreonTextMeasurer {
+    /// How far `ch` moves the pen to the right, in pixels.
+    fn advance(&self, ch: char, style: &PreonTextStyle) -> f32;
+
+    /// Adjustment of the space between `previous` and `ch`, usually negative.
+    fn kerning(&self, _previous: char, _ch: char, _style: &PreonTextStyle) -> f32 {
+        0.0
+    }
+
+    /// Distance between two lines of text.
+    fn line_height(&self, style: &PreonTextStyle) -> f32 {
+        style.size * 1.2
+    }
+
+    /// The width of `text` on a single line.
+    fn measure(&self, text: &str, style: &PreonTextStyle) -> f32 {
+        let mut previous = None;
+
+        text.chars().fold(0.0, |width, ch| {
+            let kerning = match previous.replace(ch) {
+                Some(previous) => self.kerning(previous, ch, style),
+                None => 0.0,
+            };
+
+            width + kerning + self.advance(ch, style)
+        })
+    }
+}
+
+/// Guesses character widths from the font size, used until a render module sets a real measurer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PreonApproximateTextMeasurer;
+
+impl PreonTextMeasurer for PreonApproximateTextMeasurer {
+    fn advance(&self, ch: char, style: &PreonTextStyle) -> f32 {
+        let em = match ch {
+            ' ' | 'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 0.3,
+            'f' | 't' | 'r' | '(' | ')' | '[' | ']' | '-' => 0.4,
+            'm' | 'w' | 'M' | 'W' | '@' => 0.85,
+            // CJK characters and most other full-width characters
+            '\u{1100}'..='\u{115f}' | '\u{2e80}'..='\u{a4cf}' | '\u{ac00}'..='\u{d7a3}' | '\u{f900}'..='\u{faff}'
+            | '\u{fe30}'..='\u{fe4f}' | '\u{ff00}'..='\u{ff60}' | '\u{ffe0}'..='\u{ffe6}' => 1.0,
+            _ if ch.is_uppercase() => 0.65,
+            _ => 0.55,
+        };
+
+        em * style.size
+    }
+}
@@ -0,0 +1,505 @@
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Display, iter::Peekable, str::CharIndices};
+
+use crate::{traversal::PreonDepthFirst, PreonComponentHandle, PreonEngine};
+
+/// A parsed selector, used to look up components with [`PreonEngine::select`].
+///
+/// ### Supported syntax
+///
+/// Selector|Matches
+/// :--|:--
+/// `*`|Any component
+/// `#label`|The component with id `label`
+/// `.row`|Components with class `row`
+/// `:nth-child(2)`|Components that are the 2nd child of their parent, `An+B`, `odd` and `even` are also supported
+/// `.list > .row`|`.row` components whose parent is a `.list` component
+/// `.list .row`|`.row` components with a `.list` component somewhere above them
+///
+/// Simple selectors can be combined, so `.row.selected:nth-child(odd)` matches odd children
+/// with both the `row` and `selected` class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreonSelector {
+    parts: Vec<PreonSelectorPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PreonSelectorPart {
+    /// How this part relates to the part before it, ignored for the first part.
+    combinator: PreonCombinator,
+    compound: PreonCompoundSelector,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PreonCombinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct PreonCompoundSelector {
+    id: Option<String>,
+    classes: Vec<String>,
+    /// `(a, b)` in `:nth-child(an+b)`
+    nth_child: Vec<(i32, i32)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreonSelectorError {
+    Empty,
+    UnexpectedCharacter(usize, char),
+    MissingName(usize),
+    UnknownPseudoClass(String),
+    InvalidNthChild(String),
+    DanglingCombinator,
+}
+
+impl Display for PreonSelectorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PreonSelectorError::Empty => write!(f, "Empty selector"),
+            PreonSelectorError::UnexpectedCharacter(position, ch) => {
+                write!(f, "Unexpected character '{}' at {}", ch, position)
+            }
+            PreonSelectorError::MissingName(position) => {
+                write!(f, "Expected an id or class name at {}", position)
+            }
+            PreonSelectorError::UnknownPseudoClass(name) => {
+                write!(f, "Unknown pseudo-class :{}", name)
+            }
+            PreonSelectorError::InvalidNthChild(argument) => {
+                write!(f, "Invalid :nth-child({}), expected An+B, odd or even", argument)
+            }
+            PreonSelectorError::DanglingCombinator => {
+                write!(f, "Combinator '>' needs a selector on both sides")
+            }
+        }
+    }
+}
+
+impl PreonSelector {
+    pub fn parse(selector: &str) -> Result<PreonSelector, PreonSelectorError> {
+        let mut chars = selector.char_indices().peekable();
+        let mut parts: Vec<PreonSelectorPart> = Vec::new();
+        let mut current: Option<PreonCompoundSelector> = None;
+        let mut combinator = PreonCombinator::Descendant;
+        let mut expect_compound = false;
+
+        while let Some(&(position, ch)) = chars.peek() {
+            match ch {
+                ch if ch.is_whitespace() => {
+                    chars.next();
+
+                    if let Some(compound) = current.take() {
+                        parts.push(PreonSelectorPart {
+                            combinator,
+                            compound,
+                        });
+                        combinator = PreonCombinator::Descendant;
+                    }
+                }
+                '>' => {
+                    chars.next();
+
+                    if let Some(compound) = current.take() {
+                        parts.push(PreonSelectorPart {
+                            combinator,
+                            compound,
+                        });
+                    }
+
+                    if parts.is_empty() || expect_compound {
+                        return Err(PreonSelectorError::DanglingCombinator);
+                    }
+
+                    combinator = PreonCombinator::Child;
+                    expect_compound = true;
+                }
+                '*' => {
+                    chars.next();
+                    current.get_or_insert_with(Default::default);
+                    expect_compound = false;
+                }
+                '#' => {
+                    chars.next();
+                    let name = Self::parse_name(&mut chars, position)?;
+                    current.get_or_insert_with(Default::default).id = Some(name);
+                    expect_compound = false;
+                }
+                '.' => {
+                    chars.next();
+                    let name = Self::parse_name(&mut chars, position)?;
+                    current
+                        .get_or_insert_with(Default::default)
+                        .classes
+                        .push(name);
+                    expect_compound = false;
+                }
+                ':' => {
+                    chars.next();
+                    let name = Self::parse_name(&mut chars, position)?;
+                    if name != "nth-child" {
+                        return Err(PreonSelectorError::UnknownPseudoClass(name));
+                    }
+
+                    match chars.next() {
+                        Some((_, '(')) => {}
+                        Some((position, ch)) => {
+                            return Err(PreonSelectorError::UnexpectedCharacter(position, ch))
+                        }
+                        None => return Err(PreonSelectorError::InvalidNthChild(String::new())),
+                    }
+
+                    let mut argument = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, ')')) => break,
+                            Some((_, ch)) => argument.push(ch),
+                            None => return Err(PreonSelectorError::InvalidNthChild(argument)),
+                        }
+                    }
+
+                    let nth = Self::parse_nth(&argument)
+                        .ok_or(PreonSelectorError::InvalidNthChild(argument))?;
+                    current
+                        .get_or_insert_with(Default::default)
+                        .nth_child
+                        .push(nth);
+                    expect_compound = false;
+                }
+                ch => return Err(PreonSelectorError::UnexpectedCharacter(position, ch)),
+            }
+        }
+
+        if let Some(compound) = current.take() {
+            parts.push(PreonSelectorPart {
+                combinator,
+                compound,
+            });
+        }
+
+        if expect_compound {
+            Err(PreonSelectorError::DanglingCombinator)
+        } else if parts.is_empty() {
+            Err(PreonSelectorError::Empty)
+        } else {
+            Ok(PreonSelector { parts })
+        }
+    }
+
+    fn parse_name(
+        chars: &mut Peekable<CharIndices>,
+        position: usize,
+    ) -> Result<String, PreonSelectorError> {
+        let mut name = String::new();
+
+        while let Some(&(_, ch)) = chars.peek() {
+            if ch.is_alphanumeric() || ch == '-' || ch == '_' {
+                name.push(ch);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if name.is_empty() {
+            Err(PreonSelectorError::MissingName(position))
+        } else {
+            Ok(name)
+        }
+    }
+
+    /// Parses the `An+B` argument of `:nth-child()` into `(A, B)`
+    fn parse_nth(argument: &str) -> Option<(i32, i32)> {
+        let argument: String = argument
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+
+        match argument.as_str() {
+            "odd" => return Some((2, 1)),
+            "even" => return Some((2, 0)),
+            _ => (),
+        }
+
+        match argument.split_once('n') {
+            Some((a, b)) => {
+                let a = match a {
+                    "" | "+" => 1,
+                    "-" => -1,
+                    a => a.parse().ok()?,
+                };
+                let b = if b.is_empty() { 0 } else { b.parse().ok()? };
+
+                Some((a, b))
+            }
+            None => Some((0, argument.parse().ok()?)),
+        }
+    }
+
+    /// Returns true if `handle` is matched by this selector.
+    pub fn matches(&self, engine: &PreonEngine, handle: PreonComponentHandle) -> bool {
+        self.matches_part(engine, handle, self.parts.len() - 1)
+    }
+
+    fn matches_part(&self, engine: &PreonEngine, handle: PreonComponentHandle, index: usize) -> bool {
+        let part = &self.parts[index];
+
+        if !part.compound.matches(engine, handle) {
+            return false;
+        }
+
+        if index == 0 {
+            return true;
+        }
+
+        match part.combinator {
+            PreonCombinator::Child => engine
+                .ancestors(handle)
+                .next()
+                .is_some_and(|parent| self.matches_part(engine, parent, index - 1)),
+            PreonCombinator::Descendant => engine
+                .ancestors(handle)
+                .any(|ancestor| self.matches_part(engine, ancestor, index - 1)),
+        }
+    }
+}
+
+impl PreonCompoundSelector {
+    fn matches(&self, engine: &PreonEngine, handle: PreonComponentHandle) -> bool {
+        let component = match engine.get_component(handle) {
+            Some(component) => component,
+            None => return false,
+        };
+
+        if let Some(ref id) = self.id {
+            if component.id() != Some(id.as_str()) {
+                return false;
+            }
+        }
+
+        if !self
+            .classes
+            .iter()
+            .all(|class| component.classes.iter().any(|c| c == class))
+        {
+            return false;
+        }
+
+        if !self.nth_child.is_empty() {
+            let position = component
+                .parent
+                .and_then(|parent| engine.get_component(parent))
+                .and_then(|parent| parent.children.iter().position(|&c| c == handle));
+
+            let position = match position {
+                Some(position) => position as i32 + 1,
+                None => return false,
+            };
+
+            return self.nth_child.iter().all(|&(a, b)| {
+                if a == 0 {
+                    position == b
+                } else {
+                    (position - b) % a == 0 && (position - b) / a >= 0
+                }
+            });
+        }
+
+        true
+    }
+}
+
+impl core::str::FromStr for PreonSelector {
+    type Err = PreonSelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PreonSelector::parse(s)
+    }
+}
+
+impl Display for PreonSelector {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, part) in self.parts.iter().enumerate() {
+            if index > 0 {
+                match part.combinator {
+                    PreonCombinator::Descendant => write!(f, " ")?,
+                    PreonCombinator::Child => write!(f, " > ")?,
+                }
+            }
+
+            let compound = &part.compound;
+            if compound.id.is_none() && compound.classes.is_empty() && compound.nth_child.is_empty()
+            {
+                write!(f, "*")?;
+            }
+            if let Some(ref id) = compound.id {
+                write!(f, "#{}", id)?;
+            }
+            for class in compound.classes.iter() {
+                write!(f, ".{}", class)?;
+            }
+            for (a, b) in compound.nth_child.iter() {
+                write!(f, ":nth-child({}n{:+})", a, b)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Iterator over all components matching a [`PreonSelector`], in tree order. See [`PreonEngine::select`].
+pub struct PreonSelection<'a> {
+    engine: &'a PreonEngine,
+    selector: PreonSelector,
+    walk: PreonDepthFirst<'a>,
+}
+
+impl<'a> PreonSelection<'a> {
+    pub(crate) fn new(engine: &'a PreonEngine, selector: PreonSelector) -> Self {
+        Self {
+            walk: engine.depth_first(engine.root),
+            engine,
+            selector,
+        }
+    }
+}
+
+impl<'a> Iterator for PreonSelection<'a> {
+    type Item = PreonComponentHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Self {
+            engine,
+            selector,
+            walk,
+        } = self;
+
+        walk.find(|&handle| selector.matches(engine, handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec::Vec};
+
+    use super::{PreonCombinator, PreonSelector, PreonSelectorError};
+    use crate::{
+        components::{panel::PreonComponentBuilderPanelExtension, PreonComponentBuilder},
+        PreonComponentHandle, PreonEngine,
+    };
+
+    #[test]
+    fn parses_simple_selectors() {
+        let selector = PreonSelector::parse("*").unwrap();
+        assert_eq!(selector.parts.len(), 1);
+        assert_eq!(selector.parts[0].compound, Default::default());
+
+        let selector = PreonSelector::parse("#label").unwrap();
+        assert_eq!(selector.parts[0].compound.id.as_deref(), Some("label"));
+
+        let selector = PreonSelector::parse(".row.selected").unwrap();
+        assert_eq!(selector.parts[0].compound.classes, ["row", "selected"]);
+
+        let selector = PreonSelector::parse("#list.row:nth-child(2n+1):nth-child(-n+3)").unwrap();
+        let compound = &selector.parts[0].compound;
+        assert_eq!(compound.id.as_deref(), Some("list"));
+        assert_eq!(compound.classes, ["row"]);
+        assert_eq!(compound.nth_child, [(2, 1), (-1, 3)]);
+
+        assert_eq!(PreonSelector::parse(":nth-child(odd)").unwrap().parts[0].compound.nth_child, [(2, 1)]);
+        assert_eq!(PreonSelector::parse(":nth-child( even )").unwrap().parts[0].compound.nth_child, [(2, 0)]);
+        assert_eq!(PreonSelector::parse(":nth-child(4)").unwrap().parts[0].compound.nth_child, [(0, 4)]);
+    }
+
+    #[test]
+    fn parses_combinators() {
+        let combinators = |selector: &str| {
+            PreonSelector::parse(selector)
+                .unwrap()
+                .parts
+                .iter()
+                .skip(1)
+                .map(|part| part.combinator)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(combinators(".list .row"), [PreonCombinator::Descendant]);
+        assert_eq!(combinators(".list>.row"), [PreonCombinator::Child]);
+        assert_eq!(combinators("  .list  >  .row  "), [PreonCombinator::Child]);
+        assert_eq!(
+            combinators("#a .b > .c"),
+            [PreonCombinator::Descendant, PreonCombinator::Child]
+        );
+
+        // Parsing what's displayed gives back the same selector
+        let selector = PreonSelector::parse("#a .b>*:nth-child(odd)").unwrap();
+        assert_eq!(PreonSelector::parse(&selector.to_string()).unwrap(), selector);
+    }
+
+    #[test]
+    fn malformed_selectors_are_errors() {
+        assert_eq!(PreonSelector::parse(""), Err(PreonSelectorError::Empty));
+        assert_eq!(PreonSelector::parse("   "), Err(PreonSelectorError::Empty));
+        assert_eq!(PreonSelector::parse("#"), Err(PreonSelectorError::MissingName(0)));
+        assert_eq!(PreonSelector::parse(".a ."), Err(PreonSelectorError::MissingName(3)));
+        assert_eq!(PreonSelector::parse("> .a"), Err(PreonSelectorError::DanglingCombinator));
+        assert_eq!(PreonSelector::parse(".a >"), Err(PreonSelectorError::DanglingCombinator));
+        assert_eq!(PreonSelector::parse(".a > > .b"), Err(PreonSelectorError::DanglingCombinator));
+        assert_eq!(
+            PreonSelector::parse(":hover"),
+            Err(PreonSelectorError::UnknownPseudoClass("hover".to_string()))
+        );
+        assert_eq!(
+            PreonSelector::parse(":nth-child(2x)"),
+            Err(PreonSelectorError::InvalidNthChild("2x".to_string()))
+        );
+        assert_eq!(
+            PreonSelector::parse(":nth-child(2"),
+            Err(PreonSelectorError::InvalidNthChild("2".to_string()))
+        );
+        assert_eq!(PreonSelector::parse(":nth-child"), Err(PreonSelectorError::InvalidNthChild("".to_string())));
+
+        // There are no type or attribute selectors
+        assert_eq!(PreonSelector::parse("label"), Err(PreonSelectorError::UnexpectedCharacter(0, 'l')));
+        assert_eq!(PreonSelector::parse(".a[id]"), Err(PreonSelectorError::UnexpectedCharacter(2, '[')));
+        assert_eq!(PreonSelector::parse(".a + .b"), Err(PreonSelectorError::UnexpectedCharacter(3, '+')));
+    }
+
+    fn select(engine: &PreonEngine, selector: &str) -> Vec<PreonComponentHandle> {
+        engine.select(selector).unwrap().collect()
+    }
+
+    #[test]
+    fn matches_built_tree() {
+        let mut engine = PreonEngine::new();
+        let root = engine.root;
+
+        #[rustfmt::skip]
+        let (list, ids) = PreonComponentBuilder::new()
+            .class("list")
+            .id("list")
+            .start_panel_hex("#ffffff").class("row").id("first").end()
+            .start_panel_hex("#ffffff").class("row").class("selected").id("second")
+                .start_panel_hex("#ffffff").class("row").id("nested").end()
+            .end()
+            .start_panel_hex("#ffffff").class("row").id("third").end()
+            .build_into(&mut engine, root);
+
+        let [first, second, nested, third] = ["first", "second", "nested", "third"].map(|id| ids[id]);
+
+        assert_eq!(engine.find_by_id("list"), Some(list));
+        assert_eq!(select(&engine, "#second"), [second]);
+        assert_eq!(select(&engine, "#missing"), []);
+        assert_eq!(select(&engine, ".row"), [first, second, nested, third]);
+        assert_eq!(select(&engine, ".row.selected"), [second]);
+        assert_eq!(select(&engine, ".list > .row"), [first, second, third]);
+        assert_eq!(select(&engine, ".list .row"), [first, second, nested, third]);
+        assert_eq!(select(&engine, ".selected > *"), [nested]);
+        assert_eq!(select(&engine, ".list > .row:nth-child(odd)"), [first, third]);
+        assert_eq!(select(&engine, ".row:nth-child(1)"), [first, nested]);
+        assert_eq!(select(&engine, "* > .list"), [list]);
+        assert_eq!(engine.select_first(".row .row").unwrap(), Some(nested));
+    }
+}
@@ -0,0 +1,62 @@
+use alloc::string::String;
+use core::fmt::Display;
+
+use crate::{
+    events::{PreonKeyCode, PreonModifiers},
+    PreonComponentHandle,
+};
+
+/// A key pressed while holding exactly `modifiers`, like Ctrl+Shift+P.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PreonKeyChord {
+    pub modifiers: PreonModifiers,
+    pub key: PreonKeyCode,
+}
+
+impl PreonKeyChord {
+    pub fn new(modifiers: PreonModifiers, key: PreonKeyCode) -> Self {
+        Self { modifiers, key }
+    }
+}
+
+impl Display for PreonKeyChord {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let names = [
+            (PreonModifiers::CTRL, "Ctrl"),
+            (PreonModifiers::ALT, "Alt"),
+            (PreonModifiers::SHIFT, "Shift"),
+            (PreonModifiers::LOGO, "Logo"),
+        ];
+
+        for (modifier, name) in names {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// Returned by [`PreonEngine::add_shortcut`](crate::PreonEngine::add_shortcut) when the chord is
+/// already bound to another action in the same scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreonShortcutConflict {
+    pub chord: PreonKeyChord,
+    pub scope: Option<PreonComponentHandle>,
+    /// The action the chord is currently bound to.
+    pub action: String,
+}
+
+impl Display for PreonShortcutConflict {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.scope {
+            Some(_) => write!(
+                f,
+                "{} is already bound to \"{}\" in this subtree",
+                self.chord, self.action
+            ),
+            None => write!(f, "{} is already bound to \"{}\"", self.chord, self.action),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use alloc::vec::Vec;
+
+use crate::{components::PreonComponent, PreonComponentHandle};
+
+#[derive(Debug, Clone)]
+struct PreonComponentSlot {
+    generation: u32,
+    component: Option<PreonComponent>,
+}
+
+/// Generational slot map holding every [`PreonComponent`] of a [`PreonEngine`](crate::PreonEngine).
+///
+/// Components live in a single contiguous `Vec`, a [`PreonComponentHandle`] is the index into
+/// that `Vec` plus the generation of the slot at the time of insertion. Removing a component bumps
+/// the generation of its slot, so stale handles are detected instead of silently pointing to
+/// whatever component reuses the slot later.
+#[derive(Debug, Clone, Default)]
+pub struct PreonComponentStorage {
+    slots: Vec<PreonComponentSlot>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl PreonComponentStorage {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn insert(&mut self, component: PreonComponent) -> PreonComponentHandle {
+        self.len += 1;
+
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.component = Some(component);
+
+            PreonComponentHandle::new(index, slot.generation)
+        } else {
+            let index = self.slots.len() as u32;
+            self.slots.push(PreonComponentSlot {
+                generation: 0,
+                component: Some(component),
+            });
+
+            PreonComponentHandle::new(index, 0)
+        }
+    }
+
+    pub fn remove(&mut self, handle: PreonComponentHandle) -> Option<PreonComponent> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        let component = slot.component.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+
+        Some(component)
+    }
+
+    #[inline]
+    pub fn get(&self, handle: PreonComponentHandle) -> Option<&PreonComponent> {
+        match self.slots.get(handle.index as usize) {
+            Some(slot) if slot.generation == handle.generation => slot.component.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, handle: PreonComponentHandle) -> Option<&mut PreonComponent> {
+        match self.slots.get_mut(handle.index as usize) {
+            Some(slot) if slot.generation == handle.generation => slot.component.as_mut(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, handle: PreonComponentHandle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over all live components in slot order (not tree order).
+    pub fn iter(&self) -> impl Iterator<Item = (PreonComponentHandle, &PreonComponent)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.component
+                .as_ref()
+                .map(|c| (PreonComponentHandle::new(index as u32, slot.generation), c))
+        })
+    }
+
+    /// Iterate mutably over all live components in slot order (not tree order).
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (PreonComponentHandle, &mut PreonComponent)> {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let generation = slot.generation;
+            slot.component
+                .as_mut()
+                .map(|c| (PreonComponentHandle::new(index as u32, generation), c))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::PreonComponentStorage;
+    use crate::components::PreonComponent;
+
+    fn storage_with(count: usize) -> (PreonComponentStorage, Vec<crate::PreonComponentHandle>) {
+        let mut storage = PreonComponentStorage::new();
+        let handles = (0..count).map(|_| storage.insert(PreonComponent::new())).collect();
+
+        (storage, handles)
+    }
+
+    #[test]
+    fn stale_handles_are_rejected() {
+        let (mut storage, handles) = storage_with(2);
+
+        assert!(storage.remove(handles[0]).is_some());
+        assert!(storage.get(handles[0]).is_none());
+        assert!(storage.get_mut(handles[0]).is_none());
+        assert!(storage.remove(handles[0]).is_none());
+
+        // The slot is reused, but the old handle must not see the new component
+        let reinserted = storage.insert(PreonComponent::new());
+        assert_eq!(reinserted.index(), handles[0].index());
+        assert!(!storage.contains(handles[0]));
+        assert!(storage.remove(handles[0]).is_none());
+        assert!(storage.contains(reinserted));
+        assert!(storage.contains(handles[1]));
+        assert_eq!(storage.len(), 2);
+    }
+
+    #[test]
+    fn generation_increments_on_reuse() {
+        let (mut storage, handles) = storage_with(1);
+        let mut handle = handles[0];
+        assert_eq!(handle.generation(), 0);
+
+        for generation in 1..4 {
+            storage.remove(handle).unwrap();
+            handle = storage.insert(PreonComponent::new());
+
+            assert_eq!(handle.index(), 0);
+            assert_eq!(handle.generation(), generation);
+        }
+
+        assert_eq!(storage.iter().map(|(h, _)| h).collect::<Vec<_>>(), [handle]);
+    }
+
+    #[test]
+    fn free_slots_are_reused_last_in_first_out() {
+        let (mut storage, handles) = storage_with(4);
+
+        storage.remove(handles[1]).unwrap();
+        storage.remove(handles[3]).unwrap();
+        storage.remove(handles[2]).unwrap();
+        assert_eq!(storage.len(), 1);
+
+        let reused: Vec<u32> = (0..3).map(|_| storage.insert(PreonComponent::new()).index()).collect();
+        assert_eq!(reused, [2, 3, 1]);
+
+        // No free slots left, so the storage grows
+        assert_eq!(storage.insert(PreonComponent::new()).index(), 4);
+        assert_eq!(storage.len(), 5);
+        assert!(!storage.is_empty());
+    }
+}
@@ -1,4 +1,9 @@
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
 use crate::{
+    animation::{PreonAnimatable, PreonEasing, PreonTransition},
     types::{PreonColor, PreonAlignment, PreonBorder, PreonVector, PreonCorners},
     size, layout::PreonLayout, components::PreonComponentBuilder, rendering::PreonImage, prelude::PreonFont
 };
@@ -15,7 +20,7 @@ pub enum PreonBackground {
     None,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PreonTextStyle {
     pub size: f32,
     pub font: Option<PreonFont>,
@@ -34,6 +39,36 @@ impl Default for PreonTextStyle {
     }
 }
 
+/// What happens to children that don't fit inside of a component, along one axis. See
+/// [`PreonStyle::overflow_x`] and [`PreonStyle::overflow_y`].
+///
+/// Hidden and scrolled content isn't limited by the size of the component during layout, and
+/// the component doesn't grow to fit it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreonOverflow {
+    Visible,
+    /// Clipped to the inner rect, using the corner radius of the component.
+    Hidden,
+    /// Clipped to the content rect, and scrollable like a
+    /// [scroll view](crate::components::scroll_view::PreonComponentBuilderScrollViewExtension::start_scroll_view).
+    Scroll,
+}
+
+impl PreonOverflow {
+    /// Returns true for [`PreonOverflow::Hidden`] and [`PreonOverflow::Scroll`].
+    #[inline]
+    pub fn is_clipped(self) -> bool {
+        self != PreonOverflow::Visible
+    }
+}
+
+/// Overrides applied while a component is hovered or focused, see [`PreonStyle::hover`] and [`PreonStyle::focus`].
+#[derive(Debug, Clone, Default)]
+pub struct PreonStateStyle {
+    pub background: Option<PreonBackground>,
+    pub foreground_color: Option<PreonColor>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PreonStyle {
     pub background: PreonBackground,
@@ -48,6 +83,17 @@ pub struct PreonStyle {
     pub size_flags: u8,
     pub min_size: PreonVector<i32>,
     pub text_style: PreonTextStyle,
+    pub overflow_x: PreonOverflow,
+    pub overflow_y: PreonOverflow,
+    /// From 0 (invisible) to 1, multiplied with the opacity of the ancestors.
+    pub opacity: f32,
+    /// Animate these properties when they change, see [`PreonTransition`].
+    pub transitions: Vec<PreonTransition>,
+    /// Applied while the mouse is over the component, see [`PreonComponent::is_hovered`](crate::components::PreonComponent::is_hovered).
+    pub hover: PreonStateStyle,
+    /// Applied while the component has keyboard focus, see [`PreonComponent::is_focused`](crate::components::PreonComponent::is_focused).
+    /// Hover overrides take precedence.
+    pub focus: PreonStateStyle,
 }
 
 impl PreonStyle {
@@ -65,6 +111,12 @@ impl PreonStyle {
             size_flags: size::FIT,
             min_size: PreonVector::zero(),
             text_style: PreonTextStyle::default(),
+            overflow_x: PreonOverflow::Visible,
+            overflow_y: PreonOverflow::Visible,
+            opacity: 1.0,
+            transitions: Vec::new(),
+            hover: PreonStateStyle::default(),
+            focus: PreonStateStyle::default(),
         }
     }
 
@@ -79,6 +131,21 @@ impl PreonStyle {
     pub fn has_size_flag(&self, flag: u8) -> bool {
         (self.size_flags & flag) == flag
     }
+
+    /// Returns true if changing from `self` to `other` requires a relayout. Colors, backgrounds,
+    /// corner radii, text styles and opacity only need a repaint.
+    pub fn layout_differs(&self, other: &PreonStyle) -> bool {
+        self.align_items != other.align_items
+            || self.cross_align_items != other.cross_align_items
+            || self.layout != other.layout
+            || self.margin != other.margin
+            || self.padding != other.padding
+            || self.border != other.border
+            || self.size_flags != other.size_flags
+            || self.min_size != other.min_size
+            || self.overflow_x != other.overflow_x
+            || self.overflow_y != other.overflow_y
+    }
 }
 
 impl Default for PreonStyle {
@@ -91,6 +158,12 @@ pub trait PreonComponentBuilderStyleExtension {
     fn background_image(&mut self, image: &PreonImage) -> &mut PreonComponentBuilder;
     fn background_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder;
     fn foreground_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder;
+    fn hover_background_image(&mut self, image: &PreonImage) -> &mut PreonComponentBuilder;
+    fn hover_background_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder;
+    fn hover_foreground_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder;
+    fn focus_background_image(&mut self, image: &PreonImage) -> &mut PreonComponentBuilder;
+    fn focus_background_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder;
+    fn focus_foreground_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder;
     fn align_items(&mut self, alignment: PreonAlignment) -> &mut PreonComponentBuilder;
     fn cross_align_items(&mut self, alignment: PreonAlignment) -> &mut PreonComponentBuilder;
     fn layout(&mut self, layout: PreonLayout) -> &mut PreonComponentBuilder;
@@ -99,6 +172,13 @@ pub trait PreonComponentBuilderStyleExtension {
     fn border(&mut self, border: PreonBorder) -> &mut PreonComponentBuilder;
     fn corner_radius(&mut self, corners: PreonCorners) -> &mut PreonComponentBuilder;
     fn min_size(&mut self, min_size: PreonVector<i32>) -> &mut PreonComponentBuilder;
+    fn overflow(&mut self, overflow: PreonOverflow) -> &mut PreonComponentBuilder;
+    fn overflow_x(&mut self, overflow: PreonOverflow) -> &mut PreonComponentBuilder;
+    fn overflow_y(&mut self, overflow: PreonOverflow) -> &mut PreonComponentBuilder;
+    fn opacity(&mut self, opacity: f32) -> &mut PreonComponentBuilder;
+    /// Animates `property` over `duration` whenever it changes, including hover and focus
+    /// changes. See [`PreonTransition`].
+    fn transition(&mut self, property: PreonAnimatable, duration: Duration, easing: PreonEasing) -> &mut PreonComponentBuilder;
     fn fit_children(&mut self) -> &mut PreonComponentBuilder;
     fn fit_children_horizontally(&mut self) -> &mut PreonComponentBuilder;
     fn fit_children_vertically(&mut self) -> &mut PreonComponentBuilder;
@@ -125,6 +205,36 @@ impl PreonComponentBuilderStyleExtension for PreonComponentBuilder {
         self
     }
 
+    fn hover_background_image(&mut self, image: &PreonImage) -> &mut PreonComponentBuilder {
+        self.current_mut().style.hover.background = Some(PreonBackground::Image(image.clone()));
+        self
+    }
+
+    fn hover_background_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder {
+        self.current_mut().style.hover.background = Some(PreonBackground::Color(color));
+        self
+    }
+
+    fn hover_foreground_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder {
+        self.current_mut().style.hover.foreground_color = Some(color);
+        self
+    }
+
+    fn focus_background_image(&mut self, image: &PreonImage) -> &mut PreonComponentBuilder {
+        self.current_mut().style.focus.background = Some(PreonBackground::Image(image.clone()));
+        self
+    }
+
+    fn focus_background_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder {
+        self.current_mut().style.focus.background = Some(PreonBackground::Color(color));
+        self
+    }
+
+    fn focus_foreground_color(&mut self, color: PreonColor) -> &mut PreonComponentBuilder {
+        self.current_mut().style.focus.foreground_color = Some(color);
+        self
+    }
+
     fn align_items(&mut self, alignment: PreonAlignment) -> &mut PreonComponentBuilder {
         self.current_mut().style.align_items = alignment;
         self
@@ -165,6 +275,35 @@ impl PreonComponentBuilderStyleExtension for PreonComponentBuilder {
         self
     }
 
+    fn overflow(&mut self, overflow: PreonOverflow) -> &mut PreonComponentBuilder {
+        self.overflow_x(overflow).overflow_y(overflow)
+    }
+
+    fn overflow_x(&mut self, overflow: PreonOverflow) -> &mut PreonComponentBuilder {
+        self.current_mut().style.overflow_x = overflow;
+        self
+    }
+
+    fn overflow_y(&mut self, overflow: PreonOverflow) -> &mut PreonComponentBuilder {
+        self.current_mut().style.overflow_y = overflow;
+        self
+    }
+
+    fn opacity(&mut self, opacity: f32) -> &mut PreonComponentBuilder {
+        self.current_mut().style.opacity = opacity;
+        self
+    }
+
+    fn transition(&mut self, property: PreonAnimatable, duration: Duration, easing: PreonEasing) -> &mut PreonComponentBuilder {
+        let transitions = &mut self.current_mut().style.transitions;
+
+        // A property only has a single transition
+        transitions.retain(|transition| transition.property != property);
+        transitions.push(PreonTransition::new(property, duration, easing));
+
+        self
+    }
+
     fn fit_children(&mut self) -> &mut PreonComponentBuilder {
         self.current_mut().style.size_flags |= size::FIT;
         self
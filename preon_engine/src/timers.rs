@@ -0,0 +1,22 @@
+use core::time::Duration;
+
+/// Identifies a timer, see [`PreonEngine::set_timeout`](crate::PreonEngine::set_timeout) and
+/// [`PreonEngine::set_interval`](crate::PreonEngine::set_interval).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PreonTimerId(pub(crate) u32);
+
+impl PreonTimerId {
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PreonTimer {
+    pub(crate) id: PreonTimerId,
+    /// Engine time at which the timer fires next, see [`PreonUserEvent::Tick`](crate::events::PreonUserEvent::Tick).
+    pub(crate) due: Duration,
+    /// Set for timers created with [`PreonEngine::set_interval`](crate::PreonEngine::set_interval).
+    pub(crate) interval: Option<Duration>,
+}
@@ -0,0 +1,239 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use crate::{PreonComponentHandle, PreonEngine};
+
+/// Pre-order depth-first walk: every component comes before its children. See [`PreonEngine::depth_first`].
+pub struct PreonDepthFirst<'a> {
+    engine: &'a PreonEngine,
+    stack: Vec<PreonComponentHandle>,
+}
+
+impl<'a> PreonDepthFirst<'a> {
+    pub(crate) fn new(engine: &'a PreonEngine, start: PreonComponentHandle) -> Self {
+        Self {
+            engine,
+            stack: vec![start],
+        }
+    }
+}
+
+impl<'a> Iterator for PreonDepthFirst<'a> {
+    type Item = PreonComponentHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let handle = self.stack.pop()?;
+
+            if let Some(component) = self.engine.get_component(handle) {
+                self.stack.extend(component.children.iter().rev());
+                return Some(handle);
+            }
+        }
+    }
+}
+
+/// Post-order depth-first walk: every component comes after its children. See [`PreonEngine::post_order`].
+pub struct PreonPostOrder<'a> {
+    engine: &'a PreonEngine,
+    /// Handles with a flag indicating if their children have already been pushed
+    stack: Vec<(PreonComponentHandle, bool)>,
+}
+
+impl<'a> PreonPostOrder<'a> {
+    pub(crate) fn new(engine: &'a PreonEngine, start: PreonComponentHandle) -> Self {
+        Self {
+            engine,
+            stack: vec![(start, false)],
+        }
+    }
+}
+
+impl<'a> Iterator for PreonPostOrder<'a> {
+    type Item = PreonComponentHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (handle, expanded) = self.stack.pop()?;
+
+            if expanded {
+                return Some(handle);
+            }
+
+            if let Some(component) = self.engine.get_component(handle) {
+                self.stack.push((handle, true));
+                self.stack
+                    .extend(component.children.iter().rev().map(|&child| (child, false)));
+            }
+        }
+    }
+}
+
+/// Breadth-first walk, level by level. See [`PreonEngine::breadth_first`].
+pub struct PreonBreadthFirst<'a> {
+    engine: &'a PreonEngine,
+    queue: VecDeque<PreonComponentHandle>,
+}
+
+impl<'a> PreonBreadthFirst<'a> {
+    pub(crate) fn new(engine: &'a PreonEngine, start: PreonComponentHandle) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+
+        Self { engine, queue }
+    }
+}
+
+impl<'a> Iterator for PreonBreadthFirst<'a> {
+    type Item = PreonComponentHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let handle = self.queue.pop_front()?;
+
+            if let Some(component) = self.engine.get_component(handle) {
+                self.queue.extend(component.children.iter());
+                return Some(handle);
+            }
+        }
+    }
+}
+
+/// Walks up the tree, from the parent of a component to the root. See [`PreonEngine::ancestors`].
+pub struct PreonAncestors<'a> {
+    engine: &'a PreonEngine,
+    current: Option<PreonComponentHandle>,
+}
+
+impl<'a> PreonAncestors<'a> {
+    pub(crate) fn new(engine: &'a PreonEngine, start: PreonComponentHandle) -> Self {
+        Self {
+            engine,
+            current: engine.get_component(start).and_then(|c| c.parent),
+        }
+    }
+}
+
+impl<'a> Iterator for PreonAncestors<'a> {
+    type Item = PreonComponentHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let handle = self.current?;
+        self.current = self.engine.get_component(handle).and_then(|c| c.parent);
+
+        Some(handle)
+    }
+}
+
+/// All other children of a component's parent, in order. See [`PreonEngine::siblings`].
+pub struct PreonSiblings<'a> {
+    handle: PreonComponentHandle,
+    children: core::slice::Iter<'a, PreonComponentHandle>,
+}
+
+impl<'a> PreonSiblings<'a> {
+    pub(crate) fn new(engine: &'a PreonEngine, handle: PreonComponentHandle) -> Self {
+        let children = engine
+            .get_component(handle)
+            .and_then(|c| c.parent)
+            .and_then(|parent| engine.get_component(parent))
+            .map(|parent| parent.children.as_slice())
+            .unwrap_or(&[]);
+
+        Self {
+            handle,
+            children: children.iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for PreonSiblings<'a> {
+    type Item = PreonComponentHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let handle = self.handle;
+        self.children.find(|&&child| child != handle).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{components::PreonComponent, PreonComponentHandle, PreonEngine};
+
+    /// ```text
+    /// root
+    /// ├ a
+    /// │ ├ a1
+    /// │ └ a2
+    /// │   └ a21
+    /// └ b
+    ///   └ b1
+    /// ```
+    fn tree() -> (PreonEngine, [PreonComponentHandle; 7]) {
+        let mut engine = PreonEngine::new();
+        let root = engine.root;
+
+        let a = engine.push_component(root, PreonComponent::new());
+        let a1 = engine.push_component(a, PreonComponent::new());
+        let a2 = engine.push_component(a, PreonComponent::new());
+        let a21 = engine.push_component(a2, PreonComponent::new());
+        let b = engine.push_component(root, PreonComponent::new());
+        let b1 = engine.push_component(b, PreonComponent::new());
+
+        (engine, [root, a, a1, a2, a21, b, b1])
+    }
+
+    fn collect(iter: impl Iterator<Item = PreonComponentHandle>) -> Vec<PreonComponentHandle> {
+        iter.collect()
+    }
+
+    #[test]
+    fn visit_order() {
+        let (engine, [root, a, a1, a2, a21, b, b1]) = tree();
+
+        assert_eq!(collect(engine.depth_first(root)), [root, a, a1, a2, a21, b, b1]);
+        assert_eq!(collect(engine.post_order(root)), [a1, a21, a2, a, b1, b, root]);
+        assert_eq!(collect(engine.breadth_first(root)), [root, a, b, a1, a2, b1, a21]);
+
+        // Subtrees stop at their own root
+        assert_eq!(collect(engine.depth_first(a)), [a, a1, a2, a21]);
+        assert_eq!(collect(engine.post_order(a2)), [a21, a2]);
+        assert_eq!(collect(engine.breadth_first(b)), [b, b1]);
+
+        assert_eq!(collect(engine.ancestors(a21)), [a2, a, root]);
+        assert_eq!(collect(engine.ancestors(b)), [root]);
+        assert_eq!(collect(engine.ancestors(root)), []);
+
+        assert_eq!(collect(engine.siblings(a1)), [a2]);
+        assert_eq!(collect(engine.siblings(a)), [b]);
+        assert_eq!(collect(engine.siblings(b1)), []);
+        assert_eq!(collect(engine.siblings(root)), []);
+    }
+
+    #[test]
+    fn single_component() {
+        let engine = PreonEngine::new();
+        let root = engine.root;
+
+        assert_eq!(collect(engine.depth_first(root)), [root]);
+        assert_eq!(collect(engine.post_order(root)), [root]);
+        assert_eq!(collect(engine.breadth_first(root)), [root]);
+        assert_eq!(collect(engine.ancestors(root)), []);
+        assert_eq!(collect(engine.siblings(root)), []);
+    }
+
+    #[test]
+    fn removed_components_are_empty() {
+        let (mut engine, [_, a, _, a2, a21, ..]) = tree();
+        engine.remove_component(a);
+
+        for handle in [a, a2, a21] {
+            assert_eq!(collect(engine.depth_first(handle)), []);
+            assert_eq!(collect(engine.post_order(handle)), []);
+            assert_eq!(collect(engine.breadth_first(handle)), []);
+            assert_eq!(collect(engine.ancestors(handle)), []);
+            assert_eq!(collect(engine.siblings(handle)), []);
+        }
+    }
+}
@@ -619,4 +619,57 @@ impl<T: PreonVectorAble> PreonRect<T> {
         self.position.x + self.size.x >= point.x &&
         self.position.y + self.size.y >= point.y
     }
+
+    /// The smallest rect containing both `self` and `other`.
+    pub fn union(&self, other: &PreonRect<T>) -> PreonRect<T> {
+        let start = PreonVector::new(
+            partial_min(self.position.x, other.position.x),
+            partial_min(self.position.y, other.position.y),
+        );
+        let end = PreonVector::new(
+            partial_max(self.position.x + self.size.x, other.position.x + other.size.x),
+            partial_max(self.position.y + self.size.y, other.position.y + other.size.y),
+        );
+
+        PreonRect::new(start, end - start)
+    }
+
+    /// The area covered by both `self` and `other`, `None` if they don't overlap.
+    pub fn intersection(&self, other: &PreonRect<T>) -> Option<PreonRect<T>> {
+        let start = PreonVector::new(
+            partial_max(self.position.x, other.position.x),
+            partial_max(self.position.y, other.position.y),
+        );
+        let end = PreonVector::new(
+            partial_min(self.position.x + self.size.x, other.position.x + other.size.x),
+            partial_min(self.position.y + self.size.y, other.position.y + other.size.y),
+        );
+
+        if start.x < end.x && start.y < end.y {
+            Some(PreonRect::new(start, end - start))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &PreonRect<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns true if the rect covers no area.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.size.x <= T::from(0u8) || self.size.y <= T::from(0u8)
+    }
+}
+
+#[inline]
+fn partial_min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+#[inline]
+fn partial_max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a { b } else { a }
 }
\ No newline at end of file
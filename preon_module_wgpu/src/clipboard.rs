@@ -0,0 +1,66 @@
+use preon_engine::{
+    clipboard::{PreonClipboard, PreonMemoryClipboard},
+    PreonEngine,
+};
+
+/// The clipboard of the system, set on the engine by [`PreonRendererWGPU::new`](crate::PreonRendererWGPU::new).
+pub struct SystemClipboard {
+    clipboard: arboard::Clipboard,
+}
+
+impl SystemClipboard {
+    /// Fails on systems without a clipboard (e.g. a Linux session without X11 or Wayland).
+    pub fn new() -> Result<Self, arboard::Error> {
+        Ok(Self {
+            clipboard: arboard::Clipboard::new()?,
+        })
+    }
+}
+
+impl PreonClipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.clipboard.get_text().ok()
+    }
+
+    fn set_text(&mut self, text: String) {
+        if let Err(e) = self.clipboard.set_text(text) {
+            log::warn!("Couldn't copy to the clipboard: {}", e);
+        }
+    }
+}
+
+/// Sets the clipboard of the system on `engine`, or a [`PreonMemoryClipboard`] if it can't be
+/// opened, so copied text at least stays inside of the app.
+pub fn set_system_clipboard(engine: &mut PreonEngine) {
+    set_clipboard_or_memory(engine, SystemClipboard::new());
+}
+
+fn set_clipboard_or_memory(engine: &mut PreonEngine, clipboard: Result<SystemClipboard, arboard::Error>) {
+    match clipboard {
+        Ok(clipboard) => engine.set_clipboard(clipboard),
+        Err(e) => {
+            log::warn!("Couldn't open the system clipboard, falling back to one inside of the app: {}", e);
+            engine.set_clipboard(PreonMemoryClipboard::new());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use preon_engine::PreonEngine;
+
+    use super::set_clipboard_or_memory;
+
+    #[test]
+    fn falls_back_to_memory_clipboard() {
+        let mut engine = PreonEngine::new();
+        engine.clipboard().set_text(String::from("before"));
+
+        set_clipboard_or_memory(&mut engine, Err(arboard::Error::ClipboardNotSupported));
+
+        // A new clipboard, which keeps what's copied to it
+        assert_eq!(engine.clipboard().get_text(), None);
+        engine.clipboard().set_text(String::from("copied"));
+        assert_eq!(engine.clipboard().get_text().as_deref(), Some("copied"));
+    }
+}
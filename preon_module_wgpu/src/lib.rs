@@ -1,31 +1,52 @@
 use log::info;
-use preon_engine::{types::PreonVector, PreonEngine};
+use preon_engine::{
+    types::{PreonRect, PreonVector},
+    PreonEngine,
+};
 use shapes::ShapeManager;
 use winit::{dpi::PhysicalSize, window::Window};
 
+#[cfg(not(target_os = "android"))]
+mod clipboard;
 mod instancing;
 mod shapes;
 mod texture;
 
+#[cfg(not(target_os = "android"))]
+pub use clipboard::SystemClipboard;
+
 pub mod preon {
+    use std::time::Instant;
+
     use preon_engine::{
-        components::PreonComponent,
-        events::{PreonEvent, PreonEventEmitter, PreonUserEvent, PreonMouseButton, PreonButtonState, PreonKeyCode},
+        events::{PreonEvent, PreonEventEmitter, PreonUserEvent, PreonMouseButton, PreonButtonState, PreonKeyCode, PreonModifiers, PreonScrollDelta},
+        shortcuts::PreonKeyChord,
         types::PreonVector,
         PreonEngine,
     };
     use winit::{
-        event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+        dpi::PhysicalPosition,
+        event::{ElementState, Event, Ime, MouseScrollDelta, StartCause, WindowEvent},
         event_loop::{ControlFlow, EventLoop},
         window::WindowBuilder,
     };
 
     use crate::PreonRendererWGPU;
 
+    /// Bound to the platform's close shortcut by [`run`], closes the window.
+    pub const QUIT_ACTION: &str = "quit";
+
+    #[cfg(target_os = "macos")]
+    const QUIT_CHORD: PreonKeyChord = PreonKeyChord { modifiers: PreonModifiers::LOGO, key: PreonKeyCode::Q };
+    #[cfg(target_os = "windows")]
+    const QUIT_CHORD: PreonKeyChord = PreonKeyChord { modifiers: PreonModifiers::ALT, key: PreonKeyCode::F4 };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    const QUIT_CHORD: PreonKeyChord = PreonKeyChord { modifiers: PreonModifiers::CTRL, key: PreonKeyCode::Q };
+
     /// Initialize winit and run your app, this is sufficient for simple apps, if you plan on building something advanced you should consider starting it yourself so you can have a little more control over individual events.
     pub fn run<F>(mut engine: PreonEngine, mut callback: F)
     where
-        F: FnMut(&mut PreonComponent, PreonEvent, &mut PreonEventEmitter<PreonUserEvent>) + 'static,
+        F: FnMut(&mut PreonEngine, PreonEvent, &mut PreonEventEmitter<PreonUserEvent>) + 'static,
     {
         let event_loop = EventLoop::new();
         let window = WindowBuilder::new()
@@ -36,7 +57,11 @@ pub mod preon {
         let mut renderer = PreonRendererWGPU::new(&window, &mut engine);
         window.set_visible(true);
 
-        let (mut ctrl, mut shift, mut logo, mut alt) = (false, false, false, false);
+        // Remove it with `engine.remove_shortcut` to keep the window open
+        if let Err(conflict) = engine.add_shortcut(QUIT_CHORD, QUIT_ACTION, None) {
+            log::warn!("Not binding the close shortcut: {}", conflict);
+        }
+
         let mut user_events = PreonEventEmitter::new();
         user_events.push(PreonUserEvent::WindowOpened);
         user_events.push(PreonUserEvent::WindowResized(PreonVector {
@@ -45,18 +70,42 @@ pub mod preon {
         }));
 
         let mut await_close = false;
+        let mut last_update = Instant::now();
 
         event_loop.run(move |event, _, control_flow| match event {
             Event::RedrawRequested(_) => {
+                let now = Instant::now();
+                user_events.push(PreonUserEvent::Tick(now - last_update));
+                last_update = now;
+
                 user_events.flip();
 
                 if engine.update(&user_events) {
                     let events = engine.events.take();
                     for event in events {
-                        callback(&mut engine.tree, event, &mut user_events);
+                        match event {
+                            PreonEvent::Action(ref action) if action == QUIT_ACTION => {
+                                user_events.push(PreonUserEvent::WindowClosed);
+                            }
+                            PreonEvent::WindowClosed => await_close = true,
+                            PreonEvent::ImeCursorArea(area) => {
+                                window.set_ime_allowed(area.is_some());
+
+                                // Candidate windows open below the position
+                                if let Some(area) = area {
+                                    window.set_ime_position(PhysicalPosition::new(
+                                        area.position.x,
+                                        area.position.y + area.size.y,
+                                    ));
+                                }
+                            }
+                            _ => (),
+                        }
+
+                        callback(&mut engine, event, &mut user_events);
                     }
 
-                    if renderer.render( &mut engine) {
+                    if renderer.render(&mut engine) {
                         *control_flow = ControlFlow::Exit;
                     }
                 }
@@ -66,20 +115,22 @@ pub mod preon {
                 }
             }
             Event::RedrawEventsCleared => {
-                if user_events.buffer_len() > 0 {
+                if user_events.buffer_len() > 0 || engine.needs_update() {
                     window.request_redraw();
+                } else if let Some(due) = engine.next_timer_due() {
+                    // Timers count from the last tick
+                    *control_flow = ControlFlow::WaitUntil(last_update + due);
                 } else {
                     *control_flow = ControlFlow::Wait;
                 }
             }
+            Event::NewEvents(StartCause::ResumeTimeReached { .. }) => window.request_redraw(),
             Event::WindowEvent {
                 ref event,
                 window_id,
             } if window_id == window.id() => match event {
                 WindowEvent::CloseRequested => {
                     user_events.push(PreonUserEvent::WindowClosed);
-                    await_close = true;
-
                     window.request_redraw();
                 }
                 WindowEvent::Resized(physical_size) => {
@@ -108,6 +159,12 @@ pub mod preon {
                         position.y as i32,
                     )));
                 }
+                WindowEvent::CursorLeft { .. } => {
+                    user_events.push(PreonUserEvent::MouseLeftWindow);
+                }
+                WindowEvent::Focused(false) => {
+                    user_events.push(PreonUserEvent::WindowFocusLost);
+                }
                 WindowEvent::MouseInput {
                     // device_id,
                     state,
@@ -127,50 +184,23 @@ pub mod preon {
                         }
                     ))
                 }
-                WindowEvent::ModifiersChanged(modifier) => {
-                    ctrl = modifier.ctrl();
-                    shift = modifier.shift();
-                    logo = modifier.logo();
-                    alt = modifier.alt();
+                WindowEvent::MouseWheel { delta, .. } => {
+                    user_events.push(PreonUserEvent::MouseWheel(match *delta {
+                        MouseScrollDelta::LineDelta(x, y) => PreonScrollDelta::Lines(PreonVector::new(x, y)),
+                        MouseScrollDelta::PixelDelta(position) => PreonScrollDelta::Pixels(PreonVector::new(
+                            position.x as f32,
+                            position.y as f32,
+                        )),
+                    }));
                 }
-                #[cfg(target_os = "linux")]
-                WindowEvent::KeyboardInput {
-                    input:
-                        KeyboardInput {
-                            state: ElementState::Pressed,
-                            virtual_keycode: Some(VirtualKeyCode::Q),
-                            ..
-                        },
-                    ..
-                } if ctrl && !shift && !logo && !alt => {
-                    user_events.push(PreonUserEvent::WindowClosed);
-                    *control_flow = ControlFlow::Exit;
-                }
-                #[cfg(target_os = "macos")]
-                WindowEvent::KeyboardInput {
-                    input:
-                        KeyboardInput {
-                            state: ElementState::Pressed,
-                            virtual_keycode: Some(VirtualKeyCode::Q),
-                            ..
-                        },
-                    ..
-                } if logo && !shift && !ctrl && !alt => {
-                    user_events.push(PreonUserEvent::WindowClosed);
-                    *control_flow = ControlFlow::Exit;
-                }
-                #[cfg(target_os = "windows")]
-                WindowEvent::KeyboardInput {
-                    input:
-                        KeyboardInput {
-                            state: ElementState::Pressed,
-                            virtual_keycode: Some(VirtualKeyCode::F4),
-                            ..
-                        },
-                    ..
-                } if alt && !ctrl && !shift && !logo => {
-                    user_events.push(PreonUserEvent::WindowClosed);
-                    *control_flow = ControlFlow::Exit;
+                WindowEvent::ModifiersChanged(state) => {
+                    let mut modifiers = PreonModifiers::NONE;
+                    modifiers.set(PreonModifiers::SHIFT, state.shift());
+                    modifiers.set(PreonModifiers::CTRL, state.ctrl());
+                    modifiers.set(PreonModifiers::ALT, state.alt());
+                    modifiers.set(PreonModifiers::LOGO, state.logo());
+
+                    user_events.push(PreonUserEvent::ModifiersChanged(modifiers));
                 }
                 WindowEvent::ReceivedCharacter(ch) => {
                     user_events.push(PreonUserEvent::ReceivedCharacter(*ch));
@@ -187,12 +217,32 @@ pub mod preon {
                             Conversion { input: keycode as u32 }.kc
                         };
 
-                        user_events.push(PreonUserEvent::KeyboardInput(keycode, match input.state {
-                            ElementState::Pressed => PreonButtonState::Pressed,
-                            ElementState::Released => PreonButtonState::Released,
-                        }))
+                        user_events.push(PreonUserEvent::KeyboardInput {
+                            key: keycode,
+                            scancode: input.scancode,
+                            state: match input.state {
+                                ElementState::Pressed => PreonButtonState::Pressed,
+                                ElementState::Released => PreonButtonState::Released,
+                            },
+                        })
                     }
                 }
+                WindowEvent::Ime(ime) => match ime {
+                    Ime::Preedit(text, cursor) => {
+                        user_events.push(PreonUserEvent::ImePreedit(
+                            text.clone(),
+                            cursor.map(|(start, end)| start..end),
+                        ));
+                    }
+                    Ime::Commit(text) => {
+                        user_events.push(PreonUserEvent::ImeCommit(text.clone()));
+                    }
+                    // Drops what's left of the composition
+                    Ime::Disabled => {
+                        user_events.push(PreonUserEvent::ImePreedit(String::new(), None));
+                    }
+                    Ime::Enabled => (),
+                },
                 _ => (),
             },
             _ => (),
@@ -207,6 +257,8 @@ pub struct PreonRendererWGPU {
     config: wgpu::SurfaceConfiguration,
     size: PhysicalSize<u32>,
     shape_manager: ShapeManager,
+    /// Set when the surface has been reconfigured, and the next frame has to be drawn completely, even without damage.
+    redraw_requested: bool,
 }
 
 impl PreonRendererWGPU {
@@ -250,7 +302,8 @@ impl PreonRendererWGPU {
         .unwrap();
 
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            // The frame texture is copied into it
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
             format: surface
                 .get_supported_formats(&adapter)
                 .into_iter()
@@ -265,6 +318,10 @@ impl PreonRendererWGPU {
 
         info!("Init ShapeManager...");
         let shape_manager = ShapeManager::new(&device, &config, &queue, &mut engine.renderer_load_ops);
+        engine.set_text_measurer(shape_manager.text_measurer());
+
+        #[cfg(not(target_os = "android"))]
+        clipboard::set_system_clipboard(engine);
 
         info!("WGPU Initialized!");
 
@@ -275,6 +332,7 @@ impl PreonRendererWGPU {
             config,
             size,
             shape_manager,
+            redraw_requested: true,
         }
     }
 
@@ -291,6 +349,7 @@ impl PreonRendererWGPU {
             self.surface.configure(&self.device, &self.config);
             self.shape_manager
                 .resize(new_size, &self.queue, &self.device, &self.config);
+            self.redraw_requested = true;
 
             info!("Accepted!");
         } else {
@@ -300,13 +359,31 @@ impl PreonRendererWGPU {
 
     fn render(&mut self, engine: &mut PreonEngine) -> bool {
         self.shape_manager.update(&mut engine.renderer_load_ops, &self.device, &self.queue);
-        self.shape_manager.build(&mut engine.render_pass, &self.device, &self.queue);
+
+        let screen_size = PreonVector::new(self.config.width as i32, self.config.height as i32);
+        let screen = PreonRect::new(PreonVector::zero(), screen_size);
+
+        // Only the damaged part of the frame is drawn again, unless the frame texture is new
+        let damage = if self.redraw_requested {
+            Some(screen)
+        } else {
+            engine
+                .display_list
+                .damage_bounds()
+                .and_then(|damage| damage.intersection(&screen))
+        };
+
+        // Nothing changed on screen, keep showing the previous frame
+        let damage = match damage {
+            Some(damage) => damage,
+            None => return false,
+        };
+
+        self.redraw_requested = false;
+        self.shape_manager.build(&engine.display_list, damage, &self.device, &self.queue);
 
         let res: Result<(), wgpu::SurfaceError> = {
             let output = self.surface.get_current_texture().unwrap();
-            let view = output
-                .texture
-                .create_view(&wgpu::TextureViewDescriptor::default());
             let mut encoder = self
                 .device
                 .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -315,8 +392,9 @@ impl PreonRendererWGPU {
 
             {
                 self.shape_manager.render(
-                    PreonVector::new(self.config.width as i32, self.config.height as i32),
-                    &view,
+                    screen_size,
+                    damage,
+                    &output.texture,
                     &self.device,
                     &mut encoder,
                 );
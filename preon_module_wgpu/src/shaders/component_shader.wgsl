@@ -13,6 +13,8 @@ struct InstanceInput {
     @location(4) radius: vec4<f32>,
     @location(5) color: vec4<f32>,
     @location(6) uv_cutout: vec4<f32>,
+    @location(7) clip: vec4<f32>,
+    @location(8) clip_radius: vec4<f32>,
 };
 
 struct VertexOutput {
@@ -22,6 +24,8 @@ struct VertexOutput {
     @location(3) color: vec4<f32>,
     @location(4) use_texture: f32,
     @location(5) local_uv: vec2<f32>,
+    @location(6) clip: vec4<f32>,
+    @location(7) clip_radius: vec4<f32>,
     @builtin(position) position: vec4<f32>,
 };
 
@@ -54,6 +58,8 @@ fn vert_main(
     out.use_texture = 1.0 - max(sign(-inst.uv_cutout.x), 0.0);
     out.radius = inst.radius;
     out.color = inst.color;
+    out.clip = inst.clip;
+    out.clip_radius = inst.clip_radius;
 
     return out;
 }
@@ -67,6 +73,28 @@ var texture_sampler: sampler;
 fn frag_main(
     in: VertexOutput,
 ) -> @location(0) vec4<f32> {
+    // Outside of the clip rect, in pixels from the top left corner
+    if (in.position.x < in.clip.x || in.position.y < in.clip.y || in.position.x >= in.clip.z || in.position.y >= in.clip.w) {
+        discard;
+    }
+
+    // Distance to the rounded corners of the clip, using the radius of the closest corner
+    var clip_coverage = 1.0;
+    if (max(max(in.clip_radius.x, in.clip_radius.y), max(in.clip_radius.z, in.clip_radius.w)) > 0.0) {
+        let half_size = (in.clip.zw - in.clip.xy) * 0.5;
+        let p = in.position.xy - (in.clip.xy + half_size);
+        let radius_top = select(in.clip_radius.x, in.clip_radius.y, p.x > 0.0);
+        let radius_bottom = select(in.clip_radius.w, in.clip_radius.z, p.x > 0.0);
+        let radius = min(select(radius_top, radius_bottom, p.y > 0.0), min(half_size.x, half_size.y));
+        let q = abs(p) - half_size + vec2<f32>(radius);
+        let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+
+        clip_coverage = clamp(0.5 - distance, 0.0, 1.0);
+        if (clip_coverage <= 0.0) {
+            discard;
+        }
+    }
+
     let texture = textureSample(texture, texture_sampler, in.uv);
     let color = vec4<f32>(in.color);
 
@@ -80,5 +108,5 @@ fn frag_main(
     
     let out = mix * mask;
 
-    return out;
+    return vec4<f32>(out.rgb, out.a * clip_coverage);
 }
@@ -1,13 +1,15 @@
 use log::info;
 use preon_engine::{
-    rendering::{PreonRenderPass, PreonShape, PreonRendererLoadOperations},
-    types::PreonVector,
+    rendering::{PreonDisplayList, PreonShape, PreonRendererLoadOperations},
+    types::{PreonColor, PreonCorners, PreonRect, PreonVector},
 };
 use wgpu::util::DeviceExt;
 use winit::dpi::PhysicalSize;
 
 use crate::{shapes::text::TextShape, texture::Texture};
 
+pub use self::text::GlyphTextMeasurer;
+
 use self::{
     rect::RectShape,
     transform::Transform,
@@ -19,10 +21,28 @@ mod text;
 mod transform;
 mod vertex;
 
+/// Drawn behind everything, over the damaged part of the frame.
+const BACKGROUND: PreonColor = PreonColor {
+    r: 0.006022458,
+    g: 0.006022458,
+    b: 0.006022458,
+    a: 1.0,
+};
+
+/// The intersection of all pushed [`PreonShape::PushClip`]s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clip {
+    pub rect: PreonRect<i32>,
+    /// Only exact for clips inside of all others, otherwise the corners of the innermost clip
+    /// are used. Text is clipped to `rect` only.
+    pub radius: PreonCorners,
+}
+
 pub struct ShapeManager {
     transform: Transform,
 
     depth_texture: Texture,
+    frame_texture: Texture,
 
     rect: RectShape,
     text: TextShape,
@@ -40,6 +60,7 @@ impl ShapeManager {
     ) -> Self {
         info!("Creating depth buffer...");
         let depth_texture = Texture::new_depth(device, config);
+        let frame_texture = Texture::new_frame(device, config);
 
         info!("Initializing buffers...");
         let transform = Transform::new(device, config.width as f32, config.height as f32);
@@ -69,6 +90,7 @@ impl ShapeManager {
         Self {
             transform,
             depth_texture,
+            frame_texture,
             rect,
             text,
             vertex_buffer,
@@ -76,6 +98,11 @@ impl ShapeManager {
         }
     }
 
+    /// Measures text the same way it's drawn, see [`PreonEngine::set_text_measurer`](preon_engine::PreonEngine::set_text_measurer).
+    pub fn text_measurer(&self) -> GlyphTextMeasurer {
+        self.text.measurer()
+    }
+
     pub fn update(&mut self, load_ops: &mut PreonRendererLoadOperations, device: &wgpu::Device, queue: &wgpu::Queue) {
         self.rect.queue_load_textures(load_ops.take_textures());
         self.rect.queue_unload_textures(load_ops.take_unload_textures());
@@ -85,19 +112,63 @@ impl ShapeManager {
         self.text.unload_fonts(load_ops.take_unload_fonts());
     }
 
-    /// Translate PreonRenderPass to instanced wgpu::RenderPass instructions, and apply z_index
-    pub fn build(&mut self, pass: &PreonRenderPass, device: &wgpu::Device, queue: &wgpu::Queue) {
-        let z_step = 1.0 / (pass.len() + 1) as f32;
+    /// Translate PreonDisplayList to instanced wgpu::RenderPass instructions, and apply z_index.
+    /// The background is only drawn over `damage`, the rest of the previous frame stays.
+    pub fn build(
+        &mut self,
+        display_list: &PreonDisplayList,
+        damage: PreonRect<i32>,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        let z_step = 1.0 / (display_list.shapes().count() + 2) as f32;
 
         self.rect.instance_buffer.begin();
         // self.static_texture.instance_buffer.begin();
 
         let mut z_index: f32 = 1.0 - z_step;
 
-        for shape in pass.take() {
-            match shape {
-                PreonShape::Rect { .. } => self.rect.build(shape, z_index),
-                PreonShape::Text { .. } => self.text.build(shape, z_index),
+        self.rect.build(
+            &PreonShape::Rect {
+                position: damage.position,
+                size: damage.size,
+                color: BACKGROUND,
+                index: None,
+                radius: PreonCorners::ZERO,
+            },
+            z_index,
+            None,
+        );
+        z_index -= z_step;
+
+        // `None` if nothing is clipped
+        let mut clip: Option<Clip> = None;
+        let mut clip_stack = Vec::new();
+
+        for shape in display_list.shapes() {
+            match *shape {
+                PreonShape::Rect { .. } => self.rect.build(shape, z_index, clip),
+                PreonShape::Text { .. } => self.text.build(shape, z_index, clip.map(|clip| clip.rect)),
+                PreonShape::PushClip { position, size, radius } => {
+                    clip_stack.push(clip);
+
+                    let rect = PreonRect::new(position, size);
+                    clip = Some(match clip {
+                        Some(clip) => match clip.rect.intersection(&rect) {
+                            // Only one of the two clips shows its corners
+                            Some(intersection) if intersection == rect => Clip { rect, radius },
+                            Some(intersection) if intersection == clip.rect => clip,
+                            Some(intersection) => Clip { rect: intersection, radius },
+                            // Nested clips that don't overlap hide everything
+                            None => Clip {
+                                rect: PreonRect::new(position, PreonVector::zero()),
+                                radius: PreonCorners::ZERO,
+                            },
+                        },
+                        None => Clip { rect, radius },
+                    });
+                }
+                PreonShape::PopClip => clip = clip_stack.pop().flatten(),
             }
 
             z_index -= z_step;
@@ -107,11 +178,13 @@ impl ShapeManager {
         self.rect.instance_buffer.end(device, queue);
     }
 
-    /// Execute instanced wgpu render calls with the built wgpu::RenderPass instructions from ShapeManager::build();
+    /// Execute instanced wgpu render calls with the built wgpu::RenderPass instructions from ShapeManager::build(),
+    /// scissored to `damage`, then copy the frame to `output`.
     pub fn render(
         &mut self,
         screen_size: PreonVector<i32>,
-        view: &wgpu::TextureView,
+        damage: PreonRect<i32>,
+        output: &wgpu::Texture,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
     ) {
@@ -119,15 +192,11 @@ impl ShapeManager {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view,
+                    view: &self.frame_texture.view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.006022458,
-                            g: 0.006022458,
-                            b: 0.006022458,
-                            a: 1.0,
-                        }),
+                        // Clearing ignores the scissor rect, the background is drawn over the damage instead
+                        load: wgpu::LoadOp::Load,
                         store: true,
                     },
                 })],
@@ -141,6 +210,12 @@ impl ShapeManager {
                 }),
             });
 
+            render_pass.set_scissor_rect(
+                damage.position.x as u32,
+                damage.position.y as u32,
+                damage.size.x as u32,
+                damage.size.y as u32,
+            );
             render_pass.set_bind_group(0, &self.transform.bind_group, &[]);
             render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
             render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
@@ -149,7 +224,18 @@ impl ShapeManager {
             // self.static_texture.render(render_pass);
         }
 
-        self.text.render(device, encoder, view, screen_size);
+        self.text.render(device, encoder, &self.frame_texture.view, screen_size, damage);
+
+        // Presenting always shows the whole surface texture, which doesn't keep the previous frame
+        encoder.copy_texture_to_texture(
+            self.frame_texture.wgpu.as_image_copy(),
+            output.as_image_copy(),
+            wgpu::Extent3d {
+                width: screen_size.x as u32,
+                height: screen_size.y as u32,
+                depth_or_array_layers: 1,
+            },
+        );
     }
 
     /// Correct transformation after resizing
@@ -161,6 +247,7 @@ impl ShapeManager {
         config: &wgpu::SurfaceConfiguration,
     ) {
         self.depth_texture = Texture::new_depth(device, config);
+        self.frame_texture = Texture::new_frame(device, config);
         self.transform.resize(new_size, queue);
     }
 }
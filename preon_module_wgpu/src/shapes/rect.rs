@@ -5,7 +5,7 @@ use preon_engine::rendering::PreonShape;
 
 use crate::{
     instancing::{BufferLayout, InstanceBuffer},
-    shapes::vertex::Vertex,
+    shapes::{vertex::Vertex, Clip},
     texture::{Texture, TextureSheet},
 };
 
@@ -17,8 +17,15 @@ pub struct RectInstance {
     radius: [f32; 4],
     color: [f32; 4],
     uv_cutout: [f32; 4],
+    /// Left, top, right and bottom edge in pixels, fragments outside are discarded.
+    clip: [f32; 4],
+    /// Corner radii of the clip, in the same order as `radius`.
+    clip_radius: [f32; 4],
 }
 
+/// Used for rects outside of any [`PreonShape::PushClip`].
+const NO_CLIP: [f32; 4] = [f32::MIN, f32::MIN, f32::MAX, f32::MAX];
+
 impl BufferLayout for RectInstance {
     fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
         wgpu::VertexBufferLayout {
@@ -50,6 +57,16 @@ impl BufferLayout for RectInstance {
                     shader_location: 6,
                     format: wgpu::VertexFormat::Float32x4,
                 },
+                wgpu::VertexAttribute {
+                    offset: size_of::<[f32; 17]>() as wgpu::BufferAddress,
+                    shader_location: 7,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: size_of::<[f32; 21]>() as wgpu::BufferAddress,
+                    shader_location: 8,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -156,14 +173,14 @@ impl RectShape {
         }
     }
 
-    pub fn build(&mut self, shape: PreonShape, z_index: f32) {
+    pub fn build(&mut self, shape: &PreonShape, z_index: f32, clip: Option<Clip>) {
         if let PreonShape::Rect {
             position,
             size,
             color,
             index,
             radius,
-        } = shape
+        } = *shape
         {
             self.instance_buffer.push(RectInstance {
                 z_index,
@@ -186,7 +203,25 @@ impl RectShape {
                 uv_cutout: match index {
                     Some(index) => self.sheet.indices[index],
                     None => [-1.0, 0.0, 0.0, 0.0],
-                }
+                },
+                clip: match clip {
+                    Some(Clip { rect, .. }) => [
+                        rect.position.x as f32,
+                        rect.position.y as f32,
+                        (rect.position.x + rect.size.x) as f32,
+                        (rect.position.y + rect.size.y) as f32,
+                    ],
+                    None => NO_CLIP,
+                },
+                clip_radius: match clip {
+                    Some(Clip { radius, .. }) => [
+                        radius.top_left,
+                        radius.top_right,
+                        radius.bottom_right,
+                        radius.bottom_left,
+                    ],
+                    None => [0.0; 4],
+                },
             });
         }
     }
@@ -1,15 +1,62 @@
-use std::vec::Drain;
+use std::{cell::RefCell, rc::Rc, vec::Drain};
 
 use preon_engine::{
-    rendering::PreonShape,
-    types::PreonVector,
+    rendering::{PreonApproximateTextMeasurer, PreonShape, PreonTextMeasurer},
+    style::PreonTextStyle,
+    types::{PreonRect, PreonVector},
 };
-use wgpu_glyph::{ab_glyph, GlyphBrush, GlyphBrushBuilder, Layout, Section, Text};
+use wgpu_glyph::{
+    ab_glyph::{self, Font, FontArc, PxScaleFont, ScaleFont},
+    GlyphBrush, GlyphBrushBuilder, Layout, OwnedSection, Region, Section, Text,
+};
+
+/// A text section waiting to be drawn, with the font it uses and the clip it's drawn in.
+struct QueuedText {
+    font: usize,
+    clip: Option<PreonRect<i32>>,
+    section: OwnedSection,
+}
+
+/// Measures text with the fonts loaded into a [`TextShape`], see [`PreonEngine::set_text_measurer`](preon_engine::PreonEngine::set_text_measurer).
+#[derive(Clone)]
+pub struct GlyphTextMeasurer {
+    fonts: Rc<RefCell<Vec<FontArc>>>,
+}
+
+impl GlyphTextMeasurer {
+    /// Calls `f` with the font of `style`, `None` if it hasn't been loaded (yet).
+    fn with_font<R>(&self, style: &PreonTextStyle, f: impl FnOnce(PxScaleFont<&FontArc>) -> R) -> Option<R> {
+        let fonts = self.fonts.borrow();
+        let font = fonts.get(style.font.as_ref().map(|f| f.index()).unwrap_or(0))?;
+
+        Some(f(font.as_scaled(style.size)))
+    }
+}
+
+impl PreonTextMeasurer for GlyphTextMeasurer {
+    fn advance(&self, ch: char, style: &PreonTextStyle) -> f32 {
+        self.with_font(style, |font| font.h_advance(font.glyph_id(ch)))
+            .unwrap_or_else(|| PreonApproximateTextMeasurer.advance(ch, style))
+    }
+
+    fn kerning(&self, previous: char, ch: char, style: &PreonTextStyle) -> f32 {
+        self.with_font(style, |font| font.kern(font.glyph_id(previous), font.glyph_id(ch)))
+            .unwrap_or(0.0)
+    }
+
+    fn line_height(&self, style: &PreonTextStyle) -> f32 {
+        self.with_font(style, |font| font.height() + font.line_gap())
+            .unwrap_or_else(|| PreonApproximateTextMeasurer.line_height(style))
+    }
+}
 
 pub struct TextShape {
     brushes: Vec<Option<GlyphBrush<()>>>,
+    /// The fonts of `brushes`, shared with [`GlyphTextMeasurer`].
+    fonts: Rc<RefCell<Vec<FontArc>>>,
     staging_belt: wgpu::util::StagingBelt,
     format: wgpu::TextureFormat,
+    queue: Vec<QueuedText>,
 }
 
 impl TextShape {
@@ -19,88 +66,123 @@ impl TextShape {
         format: wgpu::TextureFormat,
     ) -> Self {
         let mut brushes = Vec::new();
+        let mut arcs = Vec::new();
 
         for font in fonts.into_iter() {
-            brushes.push(Some(
-                GlyphBrushBuilder::using_font(ab_glyph::FontArc::try_from_vec(font).unwrap())
-                    .build(device, format),
-            ));
+            let font = ab_glyph::FontArc::try_from_vec(font).unwrap();
+            arcs.push(font.clone());
+            brushes.push(Some(GlyphBrushBuilder::using_font(font).build(device, format)));
         }
 
         let staging_belt = wgpu::util::StagingBelt::new(1024);
 
         Self {
             brushes,
+            fonts: Rc::new(RefCell::new(arcs)),
             staging_belt,
             format,
+            queue: Vec::new(),
         }
     }
 
     pub fn load_fonts(&mut self, fonts: Drain<Vec<u8>>, device: &wgpu::Device) {
         for font in fonts {
-            self.brushes.push(Some(
-                GlyphBrushBuilder::using_font(ab_glyph::FontArc::try_from_vec(font).unwrap())
-                    .build(device, self.format),
-            ));
+            let font = ab_glyph::FontArc::try_from_vec(font).unwrap();
+            self.fonts.borrow_mut().push(font.clone());
+            self.brushes.push(Some(GlyphBrushBuilder::using_font(font).build(device, self.format)));
         }
     }
 
     pub fn unload_fonts(&mut self, fonts: Drain<usize>) {
         for font in fonts {
             self.brushes.remove(font);
+            self.fonts.borrow_mut().remove(font);
+        }
+    }
+
+    pub fn measurer(&self) -> GlyphTextMeasurer {
+        GlyphTextMeasurer {
+            fonts: Rc::clone(&self.fonts),
         }
     }
 
-    pub fn build(&mut self, shape: PreonShape, z_index: f32) {
+    pub fn build(&mut self, shape: &PreonShape, z_index: f32, clip: Option<PreonRect<i32>>) {
         if let PreonShape::Text {
-            text_style,
+            ref text_style,
             color,
             position,
             size,
             ref text,
-        } = shape
+        } = *shape
         {
-            let brush = self
-                .brushes
-                .get_mut(text_style.font.map(|f| f.index()).unwrap_or(0))
-                .unwrap()
-                .as_mut()
-                .unwrap();
-
             let (r, g, b, a) = color.into_f32_tuple();
 
-            brush.queue(Section {
-                screen_position: (position.x as f32, position.y as f32),
-                bounds: (size.x as f32, size.y as f32),
-                layout: Layout::default_wrap(),
-                text: vec![Text::new(text)
-                    .with_color([r, g, b, a])
-                    .with_scale(text_style.size)
-                    .with_z(z_index)],
+            // Sections are queued into their brush while rendering, grouped by clip
+            self.queue.push(QueuedText {
+                font: text_style.font.as_ref().map(|f| f.index()).unwrap_or(0),
+                clip,
+                section: Section {
+                    screen_position: (position.x as f32, position.y as f32),
+                    bounds: (size.x as f32, size.y as f32),
+                    layout: Layout::default_wrap(),
+                    text: vec![Text::new(text)
+                        .with_color([r, g, b, a])
+                        .with_scale(text_style.size)
+                        .with_z(z_index)],
+                }
+                .to_owned(),
             });
         }
     }
 
+    /// Draws the queued text, nothing outside of `damage` is touched.
     pub fn render(
         &mut self,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
         view: &wgpu::TextureView,
         screen_size: PreonVector<i32>,
+        damage: PreonRect<i32>,
     ) {
-        for brush in self.brushes.iter_mut() {
-            brush
-                .as_mut()
-                .unwrap()
-                .draw_queued(
-                    device,
-                    &mut self.staging_belt,
-                    encoder,
-                    view,
-                    screen_size.x as u32,
-                    screen_size.y as u32,
-                )
-                .unwrap();
+        let transform = wgpu_glyph::orthographic_projection(screen_size.x as u32, screen_size.y as u32);
+        let queue = std::mem::take(&mut self.queue);
+
+        // Every clip needs its own draw call, so consecutive sections sharing a clip are drawn together
+        for group in queue.chunk_by(|a, b| a.clip == b.clip) {
+            let scissor = match group[0].clip.map_or(Some(damage), |clip| clip.intersection(&damage)) {
+                Some(scissor) => scissor,
+                // Clipped away completely, or not damaged
+                None => continue,
+            };
+
+            let mut used = vec![false; self.brushes.len()];
+
+            for text in group {
+                if let Some(Some(brush)) = self.brushes.get_mut(text.font) {
+                    brush.queue(text.section.to_borrowed());
+                    used[text.font] = true;
+                }
+            }
+
+            for (brush, _) in self.brushes.iter_mut().zip(used).filter(|(_, used)| *used) {
+                brush
+                    .as_mut()
+                    .unwrap()
+                    .draw_queued_with_transform_and_scissoring(
+                        device,
+                        &mut self.staging_belt,
+                        encoder,
+                        view,
+                        transform,
+                        Region {
+                            x: scissor.position.x as u32,
+                            y: scissor.position.y as u32,
+                            width: scissor.size.x as u32,
+                            height: scissor.size.y as u32,
+                        },
+                    )
+                    .unwrap();
+            }
         }
 
         self.staging_belt.finish();
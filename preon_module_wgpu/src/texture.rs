@@ -117,6 +117,36 @@ impl Texture {
             sampler,
         }
     }
+
+    /// Keeps the previous frame around, so only damaged parts of it have to be drawn again. Copied
+    /// to the surface texture before presenting.
+    pub fn new_frame(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Texture {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Frame Texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Frame Sampler"),
+            ..Default::default()
+        });
+
+        Self {
+            raw: None,
+            wgpu: texture,
+            view,
+            sampler,
+        }
+    }
 }
 
 pub struct TextureSheet {